@@ -64,6 +64,7 @@ fn parse_csv(path: &Path, description: Option<String>) -> Result<String, Box<dyn
                 size: record.size,
                 speed: record.speed,
                 bubbles: record.bubbles,
+                ..Default::default()
             });
         }
     }
@@ -74,6 +75,7 @@ fn parse_csv(path: &Path, description: Option<String>) -> Result<String, Box<dyn
             description: description.unwrap_or("".to_string()),
             fish_legends: legends,
         }),
+        ..Default::default()
     };
     let json = SerJson::serialize_json(&data);
 
@@ -19,6 +19,7 @@ fn fish_data_from_vec(cells: &Vec<String>) -> Option<FishData> {
         size: cells.get(3).map_or(1.0, parse_fish_data),
         speed: cells.get(4).map_or(1.0, parse_fish_data),
         bubbles: cells.get(5).map_or(1.0, parse_fish_data),
+        ..Default::default()
     })
 }
 
@@ -97,6 +98,7 @@ fn parse_data(data: ValueRange) -> Option<InputData> {
         InputData {
             school: fishes,
             legend: None,
+            ..Default::default()
         }
     })
 }
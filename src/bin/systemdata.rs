@@ -25,6 +25,7 @@ fn main() {
             size,
             speed: 1.0,
             bubbles: 1.0,
+            ..Default::default()
         });
     }
 
@@ -34,6 +35,7 @@ fn main() {
         size: memory_size,
         speed: 1.0,
         bubbles: 1.0,
+        ..Default::default()
     });
 
     // Number of CPUs
@@ -45,6 +47,7 @@ fn main() {
             size,
             speed: 1.0,
             bubbles: 1.0,
+            ..Default::default()
         });
     }
 
@@ -53,18 +56,21 @@ fn main() {
         size: sys.load_average().one as f32,
         speed: 1.0,
         bubbles: 1.0,
+        ..Default::default()
     });
     fishes.push(FishData {
         fish: "royalgramma".to_string(),
         size: sys.load_average().five as f32,
         speed: 1.0,
         bubbles: 1.0,
+        ..Default::default()
     });
     fishes.push(FishData {
         fish: "royalgramma".to_string(),
         size: sys.load_average().fifteen as f32,
         speed: 1.0,
         bubbles: 1.0,
+        ..Default::default()
     });
 
     let total_memory = sys.total_memory() as f32;
@@ -84,6 +90,7 @@ fn main() {
             size,
             speed,
             bubbles: 1.0,
+            ..Default::default()
         });
     }
     //println!("{}", sys.total_memory());
@@ -91,6 +98,7 @@ fn main() {
     let data = InputData {
         school: fishes,
         legend: None,
+        ..Default::default()
     };
     let json = SerJson::serialize_json(&data);
     println!("{}", json);
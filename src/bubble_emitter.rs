@@ -0,0 +1,73 @@
+#![allow(clippy::question_mark)]
+
+use crate::{fish_speed::FishSpeed, shaders::water_particle};
+use macroquad::{
+    math::{Vec2, vec2},
+    texture::Texture2D,
+};
+use macroquad_particles::{AtlasConfig, BlendMode, EmitterConfig};
+use nanoserde::DeJson;
+
+#[derive(Clone, Debug, DeJson)]
+#[nserde(default)]
+pub struct BubbleEmitter {
+    pub texture: Option<String>,
+    pub lifetime: f32,
+    pub lifetime_randomness: f32,
+    pub size: f32,
+    pub size_randomness: f32,
+    pub explosiveness: f32,
+    pub velocity: f32,
+    pub velocity_randomness: f32,
+    pub direction_spread: f32,
+    #[nserde(proxy = "FishSpeed")]
+    pub gravity: Vec2,
+    pub atlas_columns: u16,
+    pub atlas_rows: u16,
+}
+
+impl Default for BubbleEmitter {
+    fn default() -> Self {
+        Self {
+            texture: None,
+            lifetime: 1.4,
+            lifetime_randomness: 0.9,
+            size: 1.5,
+            size_randomness: 0.9,
+            explosiveness: 0.9,
+            velocity: 5.0,
+            velocity_randomness: 0.8,
+            direction_spread: 0.5,
+            gravity: vec2(0.0, -5.0),
+            atlas_columns: 4,
+            atlas_rows: 2,
+        }
+    }
+}
+
+impl BubbleEmitter {
+    pub fn emitter_config(&self, amount: u32, texture: Texture2D) -> EmitterConfig {
+        EmitterConfig {
+            emitting: true,
+            amount,
+            lifetime: self.lifetime,
+            lifetime_randomness: self.lifetime_randomness,
+            size: self.size,
+            size_randomness: self.size_randomness,
+            explosiveness: self.explosiveness,
+            initial_velocity: self.velocity,
+            initial_velocity_randomness: self.velocity_randomness,
+            initial_direction_spread: self.direction_spread,
+            gravity: self.gravity,
+            atlas: Some(AtlasConfig::new(
+                self.atlas_columns,
+                self.atlas_rows,
+                0..self.atlas_columns * self.atlas_rows,
+            )),
+            texture: Some(texture),
+            material: Some(water_particle::material()),
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        }
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(clippy::question_mark)]
 
-use crate::{fish_config::FishConfig, scene_config::SceneConfig};
+use crate::{fish_config::FishConfig, http_config::HttpConfig, scene_config::SceneConfig};
 use macroquad::{
     file::load_string,
     texture::{Texture2D, load_texture},
@@ -18,6 +18,10 @@ pub struct Config {
     pub fishes: HashMap<String, FishConfig>,
     #[nserde(default = "water.png")]
     pub water_sprite: Option<String>,
+    pub control_port: Option<u16>,
+    #[nserde(default)]
+    pub http: HttpConfig,
+    pub stale_after: Option<f32>,
 }
 
 impl Default for Config {
@@ -29,6 +33,9 @@ impl Default for Config {
             scenes: None,
             fishes: HashMap::new(),
             water_sprite: Some("water.png".to_string()),
+            control_port: None,
+            http: HttpConfig::default(),
+            stale_after: None,
         }
     }
 }
@@ -38,7 +45,11 @@ impl Config {
         let json = load_string("config.json")
             .await
             .unwrap_or_else(|_| "{}".to_string());
-        DeJson::deserialize_json(&json).expect("Failed parsing config")
+        let config: Self = DeJson::deserialize_json(&json).expect("Failed parsing config");
+        Self {
+            http: config.http.clamped(),
+            ..config
+        }
     }
 
     pub async fn background_textures(&self) -> Vec<Texture2D> {
@@ -0,0 +1,15 @@
+use crate::{fish_data::FishData, input_data::InputData};
+use std::sync::mpsc::Sender;
+
+pub enum ControlCommand {
+    InputData(Box<InputData>),
+    NextScene,
+    Scene(usize, Sender<bool>),
+    Shader,
+    Legend,
+    State(Sender<String>),
+    UpdateFish(Box<FishData>, Sender<Result<(), &'static str>>),
+    RemoveFish(String, Sender<bool>),
+    AddFishes(String, usize, Sender<Result<(), &'static str>>),
+    RemoveFishes(String, usize, Sender<Result<(), &'static str>>),
+}
@@ -0,0 +1,464 @@
+use crate::{
+    control_command::ControlCommand, fish_data::FishData, input_data::InputData,
+    input_data_error::InputDataError,
+};
+use macroquad::prelude::{error, info};
+use nanoserde::{DeJson, SerJson};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::mpsc::{Receiver, Sender, TryIter, channel},
+    thread,
+    time::Duration,
+};
+
+/// Small HTTP server on localhost that forwards requests to the main loop.
+pub struct ControlServer {
+    commands: Receiver<ControlCommand>,
+}
+
+struct Response {
+    status: &'static str,
+    body: String,
+}
+
+#[derive(SerJson)]
+struct ErrorBody {
+    error: String,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl ControlServer {
+    const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+    const IO_TIMEOUT: Duration = Duration::from_secs(5);
+    const MAX_FISH_COUNT: usize = 100;
+
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        info!("Control server listening on http://127.0.0.1:{}", port);
+        let (sender, commands) = channel();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Err(err) = Self::handle(stream, &sender) {
+                    error!("Error handling control request: {}", err);
+                }
+            }
+        });
+        Ok(Self { commands })
+    }
+
+    pub fn commands(&self) -> TryIter<'_, ControlCommand> {
+        self.commands.try_iter()
+    }
+
+    fn handle(stream: TcpStream, sender: &Sender<ControlCommand>) -> std::io::Result<()> {
+        // Requests are handled one at a time, so a silent client must not block the others
+        stream.set_read_timeout(Some(Self::IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(Self::IO_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        let mut content_length = 0;
+        let mut content_type = String::new();
+        let mut from_browser = false;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "content-type" => content_type = value.trim().to_string(),
+                "origin" => from_browser = true,
+                _ => (),
+            }
+        }
+        // Web pages opened on the display must not be able to drive it
+        let response = if from_browser {
+            Response::new("403 Forbidden", "Requests from web pages are not allowed")
+        } else if content_length > Self::MAX_BODY_SIZE {
+            Response::new("413 Payload Too Large", "Request body too large")
+        } else {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or_default();
+            let path = parts.next().unwrap_or_default();
+            let body = String::from_utf8_lossy(&body);
+            Self::route(method, path, &content_type, &body, sender)
+        };
+        response.write(stream)
+    }
+
+    fn route(
+        method: &str,
+        path: &str,
+        content_type: &str,
+        body: &str,
+        sender: &Sender<ControlCommand>,
+    ) -> Response {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        // Browsers can only send JSON to other sites after asking for permission
+        if matches!((method, path), ("POST", "/inputdata" | "/fish"))
+            && !Self::is_json(content_type)
+        {
+            return Response::new(
+                "415 Unsupported Media Type",
+                "Content-Type must be application/json",
+            );
+        }
+        let command = match (method, path) {
+            ("GET", "/state") => return Self::state(sender),
+            ("POST", "/inputdata") => match InputData::parse(body) {
+                Ok(input_data) => ControlCommand::InputData(Box::new(input_data)),
+                Err(err) => return Response::invalid_input_data(err),
+            },
+            ("POST", "/scene/next") => ControlCommand::NextScene,
+            ("POST", "/shader") => ControlCommand::Shader,
+            ("POST", "/legend") => ControlCommand::Legend,
+            ("POST", path) if path.starts_with("/scene/") => {
+                return Self::scene(path.trim_start_matches("/scene/"), sender);
+            }
+            ("POST", "/fish") => return Self::update_fish(body, sender),
+            ("DELETE", path) if path.starts_with("/fish/") => {
+                return Self::remove_fish(path.trim_start_matches("/fish/"), sender);
+            }
+            (method @ ("POST" | "DELETE"), path) if path.starts_with("/fishes/") => {
+                return Self::fishes(method, path.trim_start_matches("/fishes/"), query, sender);
+            }
+            (_, "/state" | "/inputdata" | "/shader" | "/legend" | "/fish") => {
+                return Response::new("405 Method Not Allowed", "Method not allowed");
+            }
+            _ => return Response::new("404 Not Found", "Not found"),
+        };
+        match sender.send(command) {
+            Ok(()) => Response::done(),
+            Err(_) => Response::not_running(),
+        }
+    }
+
+    fn is_json(content_type: &str) -> bool {
+        content_type
+            .split(';')
+            .next()
+            .is_some_and(|media_type| media_type.trim().eq_ignore_ascii_case("application/json"))
+    }
+
+    fn state(sender: &Sender<ControlCommand>) -> Response {
+        match Self::ask(sender, ControlCommand::State) {
+            Ok(state) => Response::ok(state),
+            Err(response) => response,
+        }
+    }
+
+    fn scene(index: &str, sender: &Sender<ControlCommand>) -> Response {
+        let Ok(index) = index.parse() else {
+            return Response::new("404 Not Found", "Unknown scene");
+        };
+        match Self::ask(sender, |reply| ControlCommand::Scene(index, reply)) {
+            Ok(true) => Response::done(),
+            Ok(false) => Response::new("404 Not Found", "Unknown scene"),
+            Err(response) => response,
+        }
+    }
+
+    fn update_fish(body: &str, sender: &Sender<ControlCommand>) -> Response {
+        let fish_data = match FishData::deserialize_json(body) {
+            Ok(fish_data) => fish_data,
+            Err(err) => return Response::new("400 Bad Request", &err.to_string()),
+        };
+        match Self::ask(sender, |reply| {
+            ControlCommand::UpdateFish(Box::new(fish_data), reply)
+        }) {
+            Ok(Ok(())) => Response::done(),
+            Ok(Err(err)) => Response::new("400 Bad Request", err),
+            Err(response) => response,
+        }
+    }
+
+    fn remove_fish(id: &str, sender: &Sender<ControlCommand>) -> Response {
+        match Self::ask(sender, |reply| {
+            ControlCommand::RemoveFish(id.to_string(), reply)
+        }) {
+            Ok(true) => Response::done(),
+            Ok(false) => Response::new("404 Not Found", "Unknown fish"),
+            Err(response) => response,
+        }
+    }
+
+    /// Adds or removes `count` fishes of a type, one if no count is given.
+    fn fishes(
+        method: &str,
+        fish_type: &str,
+        query: &str,
+        sender: &Sender<ControlCommand>,
+    ) -> Response {
+        let count = query
+            .split('&')
+            .find_map(|param| param.strip_prefix("count="))
+            .map_or(Ok(1), str::parse);
+        let Some(count) = count.ok().filter(|count| *count <= Self::MAX_FISH_COUNT) else {
+            return Response::new("400 Bad Request", "Invalid count");
+        };
+        let fish_type = fish_type.to_string();
+        let answer = if method == "DELETE" {
+            Self::ask(sender, |reply| {
+                ControlCommand::RemoveFishes(fish_type, count, reply)
+            })
+        } else {
+            Self::ask(sender, |reply| {
+                ControlCommand::AddFishes(fish_type, count, reply)
+            })
+        };
+        match answer {
+            Ok(Ok(())) => Response::done(),
+            Ok(Err(_)) => Response::new("404 Not Found", "Unknown fish type"),
+            Err(response) => response,
+        }
+    }
+
+    /// Sends a command to the main loop and waits for its reply.
+    fn ask<T>(
+        sender: &Sender<ControlCommand>,
+        command: impl FnOnce(Sender<T>) -> ControlCommand,
+    ) -> Result<T, Response> {
+        let (reply, answer) = channel();
+        sender
+            .send(command(reply))
+            .map_err(|_| Response::not_running())?;
+        answer
+            .recv_timeout(Self::REPLY_TIMEOUT)
+            .map_err(|_| Response::new("503 Service Unavailable", "Aquarium did not respond"))
+    }
+}
+
+impl Response {
+    fn new(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            body: ErrorBody {
+                error: message.to_string(),
+                line: None,
+                column: None,
+            }
+            .serialize_json(),
+        }
+    }
+
+    fn invalid_input_data(err: InputDataError) -> Self {
+        Self {
+            status: "400 Bad Request",
+            body: ErrorBody {
+                error: err.message,
+                line: err.line,
+                column: err.column,
+            }
+            .serialize_json(),
+        }
+    }
+
+    fn ok(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            body,
+        }
+    }
+
+    fn done() -> Self {
+        Self::ok("{\"ok\":true}".to_string())
+    }
+
+    fn not_running() -> Self {
+        Self::new("503 Service Unavailable", "Aquarium is not running")
+    }
+
+    fn write(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers commands like the main loop of an aquarium with two scenes, and
+    /// passes on the commands that don't need an answer.
+    fn aquarium() -> (Sender<ControlCommand>, Receiver<ControlCommand>) {
+        let (sender, commands) = channel();
+        let (forward, received) = channel();
+        thread::spawn(move || {
+            for command in commands {
+                match command {
+                    ControlCommand::State(reply) => {
+                        let _ = reply.send("{\"scene\":0}".to_string());
+                    }
+                    ControlCommand::Scene(index, reply) => {
+                        let _ = reply.send(index < 2);
+                    }
+                    ControlCommand::UpdateFish(fish_data, reply) => {
+                        let _ = reply.send(fish_data.id.map(|_| ()).ok_or("Fish id missing"));
+                    }
+                    ControlCommand::RemoveFish(id, reply) => {
+                        let _ = reply.send(id == "job-1");
+                    }
+                    ControlCommand::AddFishes(fish_type, _, reply)
+                    | ControlCommand::RemoveFishes(fish_type, _, reply) => {
+                        let _ = reply.send(Some(()).filter(|_| fish_type == "crab").ok_or(""));
+                    }
+                    command => {
+                        let _ = forward.send(command);
+                    }
+                }
+            }
+        });
+        (sender, received)
+    }
+
+    fn route(method: &str, path: &str, body: &str) -> (Response, Receiver<ControlCommand>) {
+        let (sender, received) = aquarium();
+        let content_type = "application/json; charset=utf-8";
+        (
+            ControlServer::route(method, path, content_type, body, &sender),
+            received,
+        )
+    }
+
+    #[test]
+    fn state_is_answered_by_aquarium() {
+        let (response, _) = route("GET", "/state", "");
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(response.body, "{\"scene\":0}");
+    }
+
+    #[test]
+    fn input_data_is_sent_to_aquarium() {
+        let (response, received) =
+            route("POST", "/inputdata", "{\"school\":[{\"fish\":\"crab\"}]}");
+        assert_eq!(response.status, "200 OK");
+        match received.recv().unwrap() {
+            ControlCommand::InputData(input_data) => assert_eq!(input_data.school.len(), 1),
+            _ => panic!("Expected input data"),
+        }
+    }
+
+    #[test]
+    fn invalid_input_data_reports_location() {
+        let (response, received) =
+            route("POST", "/inputdata", "{\"school\": [\n  {\"fish\": x}\n]}");
+        assert_eq!(response.status, "400 Bad Request");
+        assert!(response.body.contains("\"line\":2"));
+        assert!(!response.body.contains("\"column\":null"));
+        assert!(received.try_recv().is_err());
+    }
+
+    #[test]
+    fn scene_must_exist() {
+        assert_eq!(route("POST", "/scene/1", "").0.status, "200 OK");
+        assert_eq!(route("POST", "/scene/2", "").0.status, "404 Not Found");
+        assert_eq!(route("POST", "/scene/first", "").0.status, "404 Not Found");
+    }
+
+    #[test]
+    fn commands_are_sent_to_aquarium() {
+        let (response, received) = route("POST", "/scene/next", "");
+        assert_eq!(response.status, "200 OK");
+        assert!(matches!(
+            received.recv().unwrap(),
+            ControlCommand::NextScene
+        ));
+        let (_, received) = route("POST", "/shader", "");
+        assert!(matches!(received.recv().unwrap(), ControlCommand::Shader));
+        let (_, received) = route("POST", "/legend", "");
+        assert!(matches!(received.recv().unwrap(), ControlCommand::Legend));
+    }
+
+    #[test]
+    fn fish_is_updated_by_id() {
+        let body = "{\"id\":\"job-1\",\"fish\":\"crab\"}";
+        assert_eq!(route("POST", "/fish", body).0.status, "200 OK");
+        let body = "{\"fish\":\"crab\"}";
+        assert_eq!(route("POST", "/fish", body).0.status, "400 Bad Request");
+        assert_eq!(route("POST", "/fish", "{").0.status, "400 Bad Request");
+    }
+
+    #[test]
+    fn fish_is_removed_by_id() {
+        assert_eq!(route("DELETE", "/fish/job-1", "").0.status, "200 OK");
+        assert_eq!(route("DELETE", "/fish/job-2", "").0.status, "404 Not Found");
+    }
+
+    #[test]
+    fn fishes_are_added_by_type() {
+        assert_eq!(route("POST", "/fishes/crab?count=3", "").0.status, "200 OK");
+        assert_eq!(route("POST", "/fishes/shark", "").0.status, "404 Not Found");
+        let (response, _) = route("POST", "/fishes/crab?count=many", "");
+        assert_eq!(response.status, "400 Bad Request");
+        let (response, _) = route("POST", "/fishes/crab?count=1000", "");
+        assert_eq!(response.status, "400 Bad Request");
+    }
+
+    #[test]
+    fn fishes_are_removed_by_type() {
+        assert_eq!(
+            route("DELETE", "/fishes/crab?count=2", "").0.status,
+            "200 OK"
+        );
+        let (response, _) = route("DELETE", "/fishes/shark", "");
+        assert_eq!(response.status, "404 Not Found");
+    }
+
+    #[test]
+    fn body_must_be_json() {
+        let (sender, received) = aquarium();
+        for (path, body) in [
+            ("/inputdata", "{\"school\":[]}"),
+            ("/fish", "{\"id\":\"a\"}"),
+        ] {
+            let response = ControlServer::route("POST", path, "text/plain", body, &sender);
+            assert_eq!(response.status, "415 Unsupported Media Type");
+            let response = ControlServer::route("POST", path, "", body, &sender);
+            assert_eq!(response.status, "415 Unsupported Media Type");
+        }
+        assert!(received.try_recv().is_err());
+    }
+
+    #[test]
+    fn wrong_method_is_not_allowed() {
+        assert_eq!(
+            route("GET", "/shader", "").0.status,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(
+            route("POST", "/state", "").0.status,
+            "405 Method Not Allowed"
+        );
+        assert_eq!(route("GET", "/fish", "").0.status, "405 Method Not Allowed");
+    }
+
+    #[test]
+    fn unknown_path_is_not_found() {
+        assert_eq!(route("GET", "/fishbowl", "").0.status, "404 Not Found");
+    }
+
+    #[test]
+    fn stopped_aquarium_is_unavailable() {
+        let (sender, commands) = channel();
+        drop(commands);
+        let response = ControlServer::route("POST", "/shader", "", "", &sender);
+        assert_eq!(response.status, "503 Service Unavailable");
+        let response = ControlServer::route("GET", "/state", "", "", &sender);
+        assert_eq!(response.status, "503 Service Unavailable");
+    }
+}
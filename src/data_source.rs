@@ -0,0 +1,30 @@
+use crate::{data_status::DataStatus, input_data::InputData};
+use std::collections::HashSet;
+
+/// What has been loaded from one input data path, which is kept while other
+/// scenes are shown.
+pub struct DataSource {
+    pub status: DataStatus,
+    /// The last data that was loaded successfully.
+    pub input_data: Option<InputData>,
+    /// Keys of the events with a time that have already been triggered.
+    pub seen_events: HashSet<String>,
+    /// Keys of the fishes in the school that died of old age.
+    pub expired_fishes: HashSet<String>,
+}
+
+impl DataSource {
+    pub fn new(stale_after: f32) -> Self {
+        Self {
+            status: DataStatus::new(stale_after),
+            input_data: None,
+            seen_events: HashSet::new(),
+            expired_fishes: HashSet::new(),
+        }
+    }
+
+    pub fn loaded(&mut self, input_data: InputData) {
+        self.status.succeeded();
+        self.input_data = Some(input_data);
+    }
+}
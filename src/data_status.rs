@@ -0,0 +1,72 @@
+use crate::{
+    input_data_error::InputDataError,
+    tank_text::{draw_tank_text, measure_tank_text},
+};
+use macroquad::{color::Color, math::Vec2, shapes::draw_rectangle, time::get_time};
+
+/// Keeps track of when the input data was last loaded successfully.
+pub struct DataStatus {
+    loaded: bool,
+    last_success: f64,
+    last_error: Option<InputDataError>,
+    stale_after: f32,
+}
+
+impl DataStatus {
+    const MURKY_COLOR: Color = Color::new(0.35, 0.4, 0.2, 0.35);
+    const FONT_COLOR: Color = Color::new(1., 1., 1., 0.6);
+    const FONT_SIZE: f32 = 1.8;
+    const MARGIN: f32 = 1.;
+
+    pub fn new(stale_after: f32) -> Self {
+        Self {
+            loaded: false,
+            last_success: get_time(),
+            last_error: None,
+            stale_after,
+        }
+    }
+
+    pub fn succeeded(&mut self) {
+        self.loaded = true;
+        self.last_success = get_time();
+        self.last_error = None;
+    }
+
+    pub fn failed(&mut self, error: InputDataError) {
+        self.last_error = Some(error);
+    }
+
+    pub fn last_error(&self) -> Option<&InputDataError> {
+        self.last_error.as_ref()
+    }
+
+    pub fn has_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Seconds since the data was last loaded, or since starting if it never was.
+    pub fn age(&self) -> f32 {
+        (get_time() - self.last_success) as f32
+    }
+
+    pub fn is_stale(&self) -> bool {
+        self.last_error.is_some() && self.age() > self.stale_after
+    }
+
+    pub fn draw(&self, rect: Vec2) {
+        if !self.is_stale() {
+            return;
+        }
+        draw_rectangle(0., 0., rect.x, rect.y, Self::MURKY_COLOR);
+        let text = format!("Data not updated for {} min", (self.age() / 60.) as u32);
+        let dimensions = measure_tank_text(&text, Self::FONT_SIZE);
+        draw_tank_text(
+            &text,
+            rect.x - dimensions.width - Self::MARGIN,
+            rect.y - Self::MARGIN,
+            Self::FONT_SIZE,
+            Self::FONT_COLOR,
+        );
+    }
+}
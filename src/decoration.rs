@@ -0,0 +1,214 @@
+use crate::{
+    decoration_config::DecorationConfig, decoration_kind::DecorationKind, prop_value::PropValue,
+    shaders::water_particle,
+};
+use macroquad::{
+    color::{Color, colors::WHITE},
+    math::{Rect, vec2},
+    shapes::{draw_rectangle, draw_rectangle_lines, draw_triangle},
+    texture::{DrawTextureParams, Texture2D, draw_texture_ex},
+};
+use macroquad_particles::{AtlasConfig, BlendMode, Emitter, EmitterConfig};
+
+pub struct Decoration {
+    kind: DecorationKind,
+    area: Rect,
+    texture: Option<Texture2D>,
+    emitter: Option<Emitter>,
+    active: bool,
+    level: f32,
+    time: f32,
+}
+
+impl Decoration {
+    const SWAY_SPEED: f32 = 1.5;
+    const SWAY_ANGLE: f32 = 0.08;
+    const DIVER_SPEED: f32 = 2.;
+    const CHEST_FRAMES: f32 = 2.;
+    const BEAM_LENGTH: f32 = 40.;
+    const BEAM_WIDTH: f32 = 8.;
+    const BEAM_SWEEP: f32 = 1.4;
+    const BEAM_COLOR: Color = Color::new(1., 0.95, 0.6, 0.4);
+    const METER_COLOR: Color = Color::new(0.4, 0.75, 1., 0.6);
+    const METER_BORDER: f32 = 0.3;
+
+    pub fn new(
+        config: &DecorationConfig,
+        texture: Option<Texture2D>,
+        bubble_texture: Texture2D,
+        state: PropValue,
+    ) -> Self {
+        let active = state.is_active();
+        let emitter = match config.kind {
+            DecorationKind::BubbleColumn => Some(Emitter::new(EmitterConfig {
+                emitting: active,
+                amount: 20,
+                lifetime: 3.,
+                lifetime_randomness: 0.5,
+                size: 1.,
+                size_randomness: 0.5,
+                initial_velocity: 3.,
+                initial_velocity_randomness: 0.5,
+                initial_direction_spread: 0.2,
+                gravity: vec2(0.0, -3.0),
+                atlas: Some(AtlasConfig::new(4, 2, 0..8)),
+                texture: Some(bubble_texture),
+                material: Some(water_particle::material()),
+                blend_mode: BlendMode::Additive,
+                ..Default::default()
+            })),
+            _ => None,
+        };
+        Self {
+            kind: config.kind,
+            area: config.area,
+            texture,
+            emitter,
+            active,
+            level: state.level(),
+            time: 0.,
+        }
+    }
+
+    /// Keeps the animation going, so that new data doesn't restart it.
+    pub fn set_state(&mut self, state: PropValue) {
+        self.active = state.is_active();
+        self.level = state.level();
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        if self.active {
+            self.time += delta;
+        }
+    }
+
+    pub fn draw(&mut self) {
+        match self.kind {
+            DecorationKind::Plant => self.draw_plant(),
+            DecorationKind::Chest => self.draw_chest(),
+            DecorationKind::BubbleColumn => self.draw_bubble_column(),
+            DecorationKind::Diver => self.draw_diver(),
+            DecorationKind::Lighthouse => self.draw_lighthouse(),
+            DecorationKind::Meter => self.draw_meter(),
+        }
+    }
+
+    fn draw_plant(&self) {
+        if let Some(texture) = &self.texture {
+            draw_texture_ex(
+                texture,
+                self.area.x,
+                self.area.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(self.area.w, self.area.h)),
+                    rotation: (self.time * Self::SWAY_SPEED).sin() * Self::SWAY_ANGLE,
+                    pivot: Some(vec2(self.area.center().x, self.area.bottom())),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    fn draw_chest(&self) {
+        if let Some(texture) = &self.texture {
+            let frame_width = texture.width() / Self::CHEST_FRAMES;
+            let frame = if self.active { 1. } else { 0. };
+            draw_texture_ex(
+                texture,
+                self.area.x,
+                self.area.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(self.area.w, self.area.h)),
+                    source: Some(Rect::new(
+                        frame * frame_width,
+                        0.,
+                        frame_width,
+                        texture.height(),
+                    )),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    fn draw_bubble_column(&mut self) {
+        let position = vec2(self.area.center().x, self.area.bottom());
+        if let Some(emitter) = &mut self.emitter {
+            emitter.config.emitting = self.active;
+            emitter.draw(position);
+        }
+    }
+
+    fn draw_diver(&self) {
+        if let Some(texture) = &self.texture {
+            let width = self.area.h * texture.width() / texture.height();
+            let range = (self.area.w - width).max(0.);
+            let phase = self.time * Self::DIVER_SPEED / range.max(1.);
+            draw_texture_ex(
+                texture,
+                self.area.x + range * (0.5 + 0.5 * phase.sin()),
+                self.area.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(width, self.area.h)),
+                    flip_x: phase.cos() > 0.,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    fn draw_lighthouse(&self) {
+        if self.active {
+            let top = vec2(self.area.center().x, self.area.y);
+            let angle = -std::f32::consts::FRAC_PI_2
+                + (self.time * Self::SWAY_SPEED).sin() * Self::BEAM_SWEEP;
+            let direction = vec2(angle.cos(), angle.sin());
+            let spread = vec2(-direction.y, direction.x) * Self::BEAM_WIDTH / 2.;
+            let end = top + direction * Self::BEAM_LENGTH;
+            let color = Color {
+                a: Self::BEAM_COLOR.a * self.level,
+                ..Self::BEAM_COLOR
+            };
+            draw_triangle(top, end + spread, end - spread, color);
+        }
+        self.draw_texture();
+    }
+
+    fn draw_meter(&self) {
+        let fill = self.area.h * self.level;
+        draw_rectangle(
+            self.area.x,
+            self.area.bottom() - fill,
+            self.area.w,
+            fill,
+            Self::METER_COLOR,
+        );
+        draw_rectangle_lines(
+            self.area.x,
+            self.area.y,
+            self.area.w,
+            self.area.h,
+            Self::METER_BORDER,
+            WHITE,
+        );
+        self.draw_texture();
+    }
+
+    fn draw_texture(&self) {
+        if let Some(texture) = &self.texture {
+            draw_texture_ex(
+                texture,
+                self.area.x,
+                self.area.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(self.area.w, self.area.h)),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
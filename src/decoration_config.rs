@@ -0,0 +1,33 @@
+#![allow(clippy::question_mark)]
+
+use crate::{decoration_kind::DecorationKind, fish_area::FishArea};
+use macroquad::math::Rect;
+use nanoserde::DeJson;
+
+#[derive(Clone, Debug, DeJson)]
+#[nserde(default)]
+pub struct DecorationConfig {
+    pub kind: DecorationKind,
+    pub texture: Option<String>,
+    #[nserde(proxy = "FishArea")]
+    pub area: Rect,
+    pub active_when: Option<String>,
+    pub prop: Option<String>,
+}
+
+impl Default for DecorationConfig {
+    fn default() -> Self {
+        Self {
+            kind: DecorationKind::Plant,
+            texture: None,
+            area: Rect {
+                x: 45.,
+                y: 47.5,
+                w: 10.,
+                h: 15.,
+            },
+            active_when: None,
+            prop: None,
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use nanoserde::DeJson;
+
+#[derive(Debug, Default, Copy, Clone, DeJson)]
+pub enum DecorationKind {
+    #[default]
+    Plant,
+    Chest,
+    BubbleColumn,
+    Diver,
+    Lighthouse,
+    Meter,
+}
@@ -0,0 +1,126 @@
+use crate::{event_kind::EventKind, shaders::water_particle};
+use macroquad::{
+    color::{Color, colors::WHITE},
+    math::{Vec2, vec2},
+    shapes::draw_rectangle,
+    texture::Texture2D,
+};
+use macroquad_particles::{AtlasConfig, BlendMode, Emitter, EmitterConfig};
+
+pub struct Effect {
+    kind: EventKind,
+    position: Vec2,
+    color: Color,
+    emitter: Option<Emitter>,
+    time: f32,
+}
+
+impl Effect {
+    const PARTICLE_DURATION: f32 = 2.5;
+    const BURST_AMOUNT: u32 = 60;
+    const SPLASH_AMOUNT: u32 = 40;
+    const SURFACE_Y: f32 = 1.;
+    const FLASH_DURATION: f32 = 0.6;
+    const FLASH_ALPHA: f32 = 0.6;
+
+    pub fn burst(position: Vec2, bubble_texture: Texture2D) -> Self {
+        let emitter = Emitter::new(EmitterConfig {
+            one_shot: true,
+            amount: Self::BURST_AMOUNT,
+            lifetime: 1.5,
+            lifetime_randomness: 0.5,
+            size: 1.5,
+            size_randomness: 0.7,
+            explosiveness: 1.,
+            initial_velocity: 12.,
+            initial_velocity_randomness: 0.6,
+            initial_direction_spread: std::f32::consts::TAU,
+            gravity: vec2(0.0, -5.0),
+            ..Self::particle_config(bubble_texture)
+        });
+        Self::new(EventKind::Burst, position, WHITE, Some(emitter))
+    }
+
+    pub fn splash(x: f32, bubble_texture: Texture2D) -> Self {
+        let emitter = Emitter::new(EmitterConfig {
+            one_shot: true,
+            amount: Self::SPLASH_AMOUNT,
+            lifetime: 2.,
+            lifetime_randomness: 0.4,
+            size: 1.2,
+            size_randomness: 0.6,
+            explosiveness: 1.,
+            initial_direction: vec2(0., 1.),
+            initial_velocity: 15.,
+            initial_velocity_randomness: 0.5,
+            initial_direction_spread: 1.2,
+            gravity: vec2(0.0, -8.0),
+            ..Self::particle_config(bubble_texture)
+        });
+        Self::new(
+            EventKind::Splash,
+            vec2(x, Self::SURFACE_Y),
+            WHITE,
+            Some(emitter),
+        )
+    }
+
+    pub fn flash(color: Color) -> Self {
+        Self::new(EventKind::Flash, Vec2::ZERO, color, None)
+    }
+
+    fn new(kind: EventKind, position: Vec2, color: Color, emitter: Option<Emitter>) -> Self {
+        Self {
+            kind,
+            position,
+            color,
+            emitter,
+            time: 0.,
+        }
+    }
+
+    fn particle_config(bubble_texture: Texture2D) -> EmitterConfig {
+        EmitterConfig {
+            atlas: Some(AtlasConfig::new(4, 2, 0..8)),
+            texture: Some(bubble_texture),
+            material: Some(water_particle::material()),
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32) {
+        self.time += delta;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let duration = match self.kind {
+            EventKind::Flash => Self::FLASH_DURATION,
+            _ => Self::PARTICLE_DURATION,
+        };
+        self.time >= duration
+    }
+
+    pub fn draw(&mut self, rect: Vec2) {
+        match self.kind {
+            EventKind::Burst | EventKind::Splash => self.draw_particles(self.position),
+            EventKind::Flash => self.draw_flash(rect),
+            EventKind::Dart | EventKind::Feed => (),
+        }
+    }
+
+    fn draw_particles(&mut self, position: Vec2) {
+        if let Some(emitter) = &mut self.emitter {
+            emitter.draw(position);
+        }
+    }
+
+    fn draw_flash(&self, rect: Vec2) {
+        let fade = 1. - (self.time / Self::FLASH_DURATION).clamp(0., 1.);
+        let color = Color {
+            a: self.color.a * Self::FLASH_ALPHA * fade,
+            ..self.color
+        };
+        draw_rectangle(0., 0., rect.x, rect.y, color);
+    }
+}
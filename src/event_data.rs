@@ -0,0 +1,28 @@
+#![allow(clippy::question_mark)]
+
+use crate::event_kind::EventKind;
+use nanoserde::{DeJson, SerJson};
+
+#[derive(Clone, Debug, Default, DeJson, SerJson)]
+#[nserde(default)]
+pub struct EventData {
+    #[nserde(rename = "type")]
+    pub kind: EventKind,
+    pub fish: Option<String>,
+    pub at: Option<String>,
+    pub x: Option<f32>,
+    pub color: Option<String>,
+}
+
+impl EventData {
+    pub fn key(&self) -> Option<String> {
+        self.at.as_ref().map(|at| {
+            format!(
+                "{:?}:{}:{}",
+                self.kind,
+                self.fish.as_deref().unwrap_or_default(),
+                at
+            )
+        })
+    }
+}
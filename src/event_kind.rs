@@ -0,0 +1,11 @@
+use nanoserde::{DeJson, SerJson};
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, DeJson, SerJson)]
+pub enum EventKind {
+    #[default]
+    Burst,
+    Dart,
+    Flash,
+    Splash,
+    Feed,
+}
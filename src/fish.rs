@@ -1,11 +1,22 @@
-use crate::{collision::Collision, motion::Motion, movement::Movement, shaders::water_particle};
+use crate::{
+    collision::Collision,
+    fish_config::FishConfig,
+    fish_data::FishData,
+    life_stage::LifeStage,
+    motion::Motion,
+    movement::Movement,
+    sprite_animation::SpriteAnimation,
+    tank_text::{draw_tank_text, measure_tank_text},
+};
 use macroquad::{
-    color::colors::WHITE,
-    math::{Rect, Vec2, vec2},
+    color::{Color, colors::WHITE},
+    material::{Material, gl_use_default_material, gl_use_material},
+    math::{Rect, Vec2, vec2, vec3, vec4},
+    models::{Mesh, Vertex, draw_mesh},
     rand::{ChooseRandom, gen_range},
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
 };
-use macroquad_particles::{AtlasConfig, BlendMode, Emitter, EmitterConfig};
+use macroquad_particles::{Emitter, EmitterConfig};
 
 pub struct Fish {
     motion: Motion,
@@ -16,95 +27,275 @@ pub struct Fish {
     collision_aversion: f32,
     already_collided: bool,
     texture: Texture2D,
+    animation: Option<SpriteAnimation>,
+    animation_time: f32,
+    wiggle: f32,
+    wiggle_phase: f32,
+    wiggle_material: Option<Material>,
+    color: Color,
+    depth: f32,
+    dart_time: f32,
+    appetite: f32,
+    stage: LifeStage,
+    stage_time: f32,
+    age: f32,
+    max_age: Option<f32>,
     emitter: Emitter,
+    data: Option<FishData>,
+    fish_type: Option<String>,
+    key: Option<(String, String)>,
 }
 
 impl Fish {
     const COLLISION_SIZE_DIFFERENCE: f32 = 2.0;
+    const LABEL_FONT_SIZE: f32 = 2.;
+    const LABEL_MARGIN: f32 = 0.5;
+    const LABEL_COLOR: Color = WHITE;
+    const WIGGLE_SEGMENTS: u16 = 8;
+    const WIGGLE_FREQUENCY: f32 = 8.;
+    const DEPTH_SCALING: f32 = 0.4;
+    const DEPTH_DARKENING: f32 = 0.5;
+    const DART_DURATION: f32 = 1.2;
+    const DART_SPEED: f32 = 4.;
+    const SPAWN_DURATION: f32 = 1.5;
+    const SPAWN_SCALE: f32 = 0.2;
+    const DEATH_DURATION: f32 = 5.;
+    const FLOAT_SPEED: f32 = 5.;
 
+    /// Size and speed are scaled down the deeper the fish swims.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
+        fish_type: &str,
+        fish_config: &FishConfig,
         fish_size: f32,
         max_speed: Vec2,
-        collision_aversion: f32,
         bounding_box: Rect,
-        movement: Movement,
+        depth: f32,
         texture: Texture2D,
-        bubble_texture: Texture2D,
-        bubble_amount: u32,
+        emitter_config: EmitterConfig,
     ) -> Self {
-        let fish_height = fish_size / (texture.width() / texture.height());
+        let depth_scale = Self::depth_scale(depth);
+        let fish_size = fish_size * depth_scale;
+        let max_speed = max_speed * depth_scale;
+        let animation = fish_config.animation;
+        let bubble_amount = emitter_config.amount;
+        let frame_size =
+            animation.map_or(texture.size(), |animation| animation.frame_size(&texture));
+        let fish_height = fish_size / (frame_size.x / frame_size.y);
         let size = vec2(fish_size, fish_height);
         let bbox_adjusted = Self::adjust_bounding_box(bounding_box, size);
+        let speed = Self::random_start_direction(max_speed);
         Self {
             motion: Motion {
                 position: Self::random_start_position(bbox_adjusted),
-                speed: Self::random_start_direction(max_speed),
+                speed,
                 max_speed,
                 acceleration: Self::random_acceleration(),
                 rotation: 0.,
                 idle: false,
+                facing_right: speed.x >= 0.,
+                turn_time: 0.,
+                turn_duration: fish_config.turn_duration,
             },
             size,
             bubble_amount,
             bounding_box_adjusted: bbox_adjusted,
-            collision_aversion,
+            collision_aversion: fish_config.collision_aversion,
             already_collided: false,
-            movement,
+            movement: fish_config.movement,
             texture,
-            emitter: Emitter::new(EmitterConfig {
-                emitting: true,
-                amount: bubble_amount,
-                lifetime: 1.4,
-                lifetime_randomness: 0.9,
-                size: 1.5,
-                size_randomness: 0.9,
-                explosiveness: 0.9,
-                initial_velocity: 5.0,
-                initial_velocity_randomness: 0.8,
-                initial_direction_spread: 0.5,
-                gravity: vec2(0.0, -5.0),
-                atlas: Some(AtlasConfig::new(4, 2, 0..8)),
-                texture: Some(bubble_texture.clone()),
-                material: Some(water_particle::material()),
-                blend_mode: BlendMode::Additive,
-                ..Default::default()
-            }),
+            animation,
+            animation_time: 0.,
+            wiggle: fish_config.wiggle,
+            wiggle_phase: gen_range(0., std::f32::consts::TAU),
+            wiggle_material: None,
+            color: WHITE,
+            depth,
+            dart_time: 0.,
+            appetite: fish_config.appetite,
+            stage: LifeStage::Spawning,
+            stage_time: 0.,
+            age: 0.,
+            max_age: None,
+            emitter: Emitter::new(emitter_config),
+            data: None,
+            fish_type: Some(fish_type.to_string()),
+            key: None,
         }
     }
 
-    pub fn tick(&mut self, delta: f32, collision_boxes: &[Rect]) {
+    pub fn tick(&mut self, delta: f32, collision_boxes: &[Rect], food: &[Vec2]) {
+        self.tick_life(delta);
+        if self.is_dying() {
+            self.motion.position.y = (self.motion.position.y - Self::FLOAT_SPEED * delta).max(0.);
+            return;
+        }
         let collision = self.collided(collision_boxes);
         let collision_box = self.collision_box();
         self.already_collided = collision_boxes
             .iter()
             .any(|cb| cb != &collision_box && cb.overlaps(&collision_box));
-        let motion = self
+        self.dart_time = (self.dart_time - delta).max(0.);
+        let delta = delta * self.dart_boost();
+        let mut motion = self
             .movement
             .tick(self.motion, self.bounding_box_adjusted, collision);
+        if let Some(target) = self.nearest_food(food) {
+            motion.steer_towards(target - self.size / 2.);
+        }
         self.motion = self
             .motion
             .move_position(delta, motion, self.bounding_box_adjusted);
+        self.tick_animation(delta);
     }
 
     pub fn draw(&mut self) {
-        if !self.motion.idle {
+        if !self.motion.idle && !self.is_dying() {
             self.emit();
         }
+        if let Some(material) = self.wiggle_material.as_ref().filter(|_| !self.is_dying()) {
+            self.draw_wiggling(material);
+            return;
+        }
+        let scale = self.life_scale();
+        let width = self.size.x * self.motion.turn_scale() * scale;
+        let height = self.size.y * scale;
         draw_texture_ex(
             &self.texture,
-            self.motion.position.x,
-            self.motion.position.y,
-            WHITE,
+            self.motion.position.x + (self.size.x - width) / 2.,
+            self.motion.position.y + (self.size.y - height) / 2.,
+            self.shaded_color(),
             DrawTextureParams {
-                dest_size: Some(self.size),
+                dest_size: Some(vec2(width, height)),
+                source: self.animation_frame(),
                 flip_x: self.swims_right(),
+                flip_y: self.is_dying(),
                 rotation: self.motion.rotation,
                 ..Default::default()
             },
         );
     }
 
+    pub fn set_anchor(&mut self, anchor_x: Option<f32>, anchor_y: Option<f32>, range: f32) {
+        let bbox = self.bounding_box_adjusted;
+        let (x, w) = anchor_x.map_or((bbox.x, bbox.w), |anchor| {
+            Self::anchored_span(bbox.x, bbox.w, anchor, range)
+        });
+        let (y, h) = anchor_y.map_or((bbox.y, bbox.h), |anchor| {
+            Self::anchored_span(bbox.y, bbox.h, anchor, range)
+        });
+        self.bounding_box_adjusted = Rect { x, y, w, h };
+        self.motion.position = Self::random_start_position(self.bounding_box_adjusted);
+    }
+
+    pub fn set_tint(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    pub fn depth(&self) -> f32 {
+        self.depth
+    }
+
+    /// The material is only used when the fish config wiggles the fish.
+    pub fn set_wiggle_material(&mut self, material: Material) {
+        if self.wiggle > 0. {
+            self.wiggle_material = Some(material);
+        }
+    }
+
+    pub fn set_max_age(&mut self, max_age: Option<f32>) {
+        self.max_age = max_age;
+    }
+
+    pub fn die(&mut self) {
+        if !self.is_dying() {
+            self.set_stage(LifeStage::Dying);
+            self.motion.rotation = 0.;
+        }
+    }
+
+    pub fn is_dying(&self) -> bool {
+        self.stage == LifeStage::Dying
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.is_dying() && self.stage_time >= Self::DEATH_DURATION
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.max_age.is_some_and(|max_age| self.age >= max_age)
+    }
+
+    pub fn can_eat(&self, food: Vec2) -> bool {
+        self.appetite > 0. && !self.is_dying() && self.collision_box().contains(food)
+    }
+
+    pub fn dart(&mut self) {
+        self.dart_time = Self::DART_DURATION;
+        self.motion.idle = false;
+    }
+
+    pub fn set_data(&mut self, data: FishData) {
+        self.data = Some(data);
+    }
+
+    pub fn data(&self) -> Option<&FishData> {
+        self.data.as_ref()
+    }
+
+    /// Identifies the input data source and the entry in its school that this
+    /// fish shows.
+    pub fn set_key(&mut self, source: &str, key: &str) {
+        self.key = Some((source.to_string(), key.to_string()));
+    }
+
+    pub fn key(&self) -> Option<(&str, &str)> {
+        self.key
+            .as_ref()
+            .map(|(source, key)| (source.as_str(), key.as_str()))
+    }
+
+    pub fn fish_type(&self) -> Option<&str> {
+        self.fish_type.as_deref()
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.data.as_ref().and_then(|data| data.id.as_deref())
+    }
+
+    pub fn take_place_of(&mut self, fish: &Fish) {
+        self.motion.position = self
+            .motion
+            .clamp(fish.motion.position, self.bounding_box_adjusted);
+        self.stage = fish.stage;
+        self.stage_time = fish.stage_time;
+        self.age = fish.age;
+    }
+
+    pub fn is_highlighted(&self) -> bool {
+        self.data.as_ref().is_some_and(|data| data.highlight)
+    }
+
+    pub fn inspection(&self) -> Vec<(String, String)> {
+        self.data
+            .as_ref()
+            .map(|data| data.fields())
+            .unwrap_or_default()
+    }
+
+    pub fn draw_label(&self) {
+        if let Some(label) = self.data.as_ref().and_then(|data| data.label.as_deref()) {
+            let dimensions = measure_tank_text(label, Self::LABEL_FONT_SIZE);
+            draw_tank_text(
+                label,
+                self.motion.position.x + (self.size.x - dimensions.width) / 2.,
+                self.motion.position.y - Self::LABEL_MARGIN,
+                Self::LABEL_FONT_SIZE,
+                Self::LABEL_COLOR,
+            );
+        }
+    }
+
     pub fn collision_box(&self) -> Rect {
         Rect {
             x: self.motion.position.x,
@@ -114,6 +305,10 @@ impl Fish {
         }
     }
 
+    fn depth_scale(depth: f32) -> f32 {
+        1. - depth.clamp(0., 1.) * Self::DEPTH_SCALING
+    }
+
     fn adjust_bounding_box(bounding_box: Rect, size: Vec2) -> Rect {
         Rect {
             x: bounding_box.x,
@@ -123,6 +318,13 @@ impl Fish {
         }
     }
 
+    fn anchored_span(start: f32, length: f32, anchor: f32, range: f32) -> (f32, f32) {
+        let center = start + length * anchor.clamp(0., 1.);
+        let from = (center - range / 2.).max(start);
+        let to = (center + range / 2.).min(start + length);
+        (from, to - from)
+    }
+
     fn random_start_position(bounding_box: Rect) -> Vec2 {
         vec2(
             gen_range(bounding_box.x, bounding_box.right()),
@@ -164,8 +366,142 @@ impl Fish {
         Collision::No
     }
 
+    fn tick_animation(&mut self, delta: f32) {
+        if self.motion.idle {
+            return;
+        }
+        if let Some(animation) = self.animation {
+            self.animation_time += delta * animation.fps * self.speed_factor();
+            self.animation_time %= animation.frames.max(1) as f32;
+        }
+    }
+
+    fn tick_life(&mut self, delta: f32) {
+        self.age += delta;
+        self.stage_time += delta;
+        match self.stage {
+            LifeStage::Spawning if self.stage_time >= Self::SPAWN_DURATION => {
+                self.set_stage(LifeStage::Swimming)
+            }
+            LifeStage::Swimming if self.is_expired() => self.die(),
+            _ => (),
+        }
+    }
+
+    fn set_stage(&mut self, stage: LifeStage) {
+        self.stage = stage;
+        self.stage_time = 0.;
+    }
+
+    fn life_scale(&self) -> f32 {
+        match self.stage {
+            LifeStage::Spawning => {
+                let progress = (self.stage_time / Self::SPAWN_DURATION).clamp(0., 1.);
+                Self::SPAWN_SCALE + (1. - Self::SPAWN_SCALE) * progress
+            }
+            _ => 1.,
+        }
+    }
+
+    fn life_alpha(&self) -> f32 {
+        match self.stage {
+            LifeStage::Spawning => (self.stage_time / Self::SPAWN_DURATION).clamp(0., 1.),
+            LifeStage::Swimming => 1.,
+            LifeStage::Dying => 1. - (self.stage_time / Self::DEATH_DURATION).clamp(0., 1.),
+        }
+    }
+
+    fn shaded_color(&self) -> Color {
+        let shade = 1. - self.depth.clamp(0., 1.) * Self::DEPTH_DARKENING;
+        Color::new(
+            self.color.r * shade,
+            self.color.g * shade,
+            self.color.b * shade,
+            self.color.a * self.life_alpha(),
+        )
+    }
+
+    fn nearest_food(&self, food: &[Vec2]) -> Option<Vec2> {
+        if self.appetite <= 0. {
+            return None;
+        }
+        let center = self.collision_box().center();
+        food.iter()
+            .filter(|position| position.distance(center) <= self.appetite)
+            .min_by(|a, b| a.distance(center).total_cmp(&b.distance(center)))
+            .copied()
+    }
+
+    fn dart_boost(&self) -> f32 {
+        1. + (Self::DART_SPEED - 1.) * self.dart_time / Self::DART_DURATION
+    }
+
+    fn speed_factor(&self) -> f32 {
+        self.motion.speed.length() / self.motion.max_speed.length().max(f32::EPSILON)
+    }
+
+    fn draw_wiggling(&self, material: &Material) {
+        let texture_size = self.texture.size();
+        let source =
+            self.animation_frame()
+                .unwrap_or(Rect::new(0., 0., texture_size.x, texture_size.y));
+        let (u_left, u_right) = (source.x / texture_size.x, source.right() / texture_size.x);
+        let (v_top, v_bottom) = (source.y / texture_size.y, source.bottom() / texture_size.y);
+        let amplitude = if self.motion.idle {
+            0.
+        } else {
+            self.wiggle * self.size.y * self.speed_factor()
+        };
+        let center = self.motion.position + self.size / 2.;
+        let (sin, cos) = self.motion.rotation.sin_cos();
+        let turn_scale = self.motion.turn_scale();
+        let life_scale = self.life_scale();
+        let color = self.shaded_color();
+        let swims_right = self.swims_right();
+
+        let mut vertices = Vec::with_capacity((Self::WIGGLE_SEGMENTS as usize + 1) * 2);
+        for segment in 0..=Self::WIGGLE_SEGMENTS {
+            let along = segment as f32 / Self::WIGGLE_SEGMENTS as f32;
+            // Fish textures face left, so the tail is at the right side of the texture
+            let tail = if swims_right { 1. - along } else { along };
+            let u = u_left + (u_right - u_left) * tail;
+            for (v, y) in [(v_top, 0.), (v_bottom, self.size.y)] {
+                let local = (vec2(along * self.size.x, y) - self.size / 2.)
+                    * vec2(turn_scale, 1.)
+                    * life_scale;
+                let position =
+                    center + vec2(local.x * cos - local.y * sin, local.x * sin + local.y * cos);
+                vertices.push(Vertex {
+                    position: vec3(position.x, position.y, 0.),
+                    uv: vec2(u, v),
+                    color: color.into(),
+                    normal: vec4(amplitude, self.wiggle_phase, tail, Self::WIGGLE_FREQUENCY),
+                });
+            }
+        }
+        let indices = (0..Self::WIGGLE_SEGMENTS)
+            .flat_map(|segment| {
+                let i = segment * 2;
+                [i, i + 1, i + 2, i + 1, i + 3, i + 2]
+            })
+            .collect();
+
+        gl_use_material(material);
+        draw_mesh(&Mesh {
+            vertices,
+            indices,
+            texture: Some(self.texture.clone()),
+        });
+        gl_use_default_material();
+    }
+
+    fn animation_frame(&self) -> Option<Rect> {
+        self.animation
+            .map(|animation| animation.source(&self.texture, self.animation_time as u32))
+    }
+
     fn swims_right(&self) -> bool {
-        self.motion.speed.x >= 0.
+        self.motion.facing_right
     }
 
     fn emit_position(&self) -> Vec2 {
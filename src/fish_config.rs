@@ -1,18 +1,30 @@
-use crate::{fish_area::FishArea, fish_speed::FishSpeed, movement::Movement};
+#![allow(clippy::question_mark)]
+
+use crate::{
+    bubble_emitter::BubbleEmitter, fish_area::FishArea, fish_data::FishData, fish_speed::FishSpeed,
+    movement::Movement, sprite_animation::SpriteAnimation, tint::parse_color,
+};
 use macroquad::{
+    color::{Color, colors::WHITE},
     math::{Rect, Vec2, vec2},
     rand::gen_range,
+    texture::Texture2D,
 };
 use nanoserde::DeJson;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, DeJson)]
 #[nserde(default)]
 pub struct FishConfig {
     pub texture: String,
+    pub animation: Option<SpriteAnimation>,
+    pub wiggle: f32,
+    pub turn_duration: f32,
     pub size: f32,
     pub size_randomness: f32,
     pub movement: Movement,
-    pub bubbles: u32,
+    pub bubbles: f32,
+    pub bubble_emitter: BubbleEmitter,
     #[nserde(proxy = "FishSpeed")]
     pub speed: Vec2,
     #[nserde(proxy = "FishSpeed")]
@@ -20,16 +32,24 @@ pub struct FishConfig {
     pub collision_aversion: f32,
     #[nserde(proxy = "FishArea")]
     pub area: Rect,
+    pub anchor_range: f32,
+    pub depth: f32,
+    pub appetite: f32,
+    pub palette: HashMap<String, String>,
 }
 
 impl Default for FishConfig {
     fn default() -> Self {
         Self {
             texture: "ferris.png".to_string(),
+            animation: None,
+            wiggle: 0.,
+            turn_duration: 0.4,
             size: 7.,
             size_randomness: 0.5,
             movement: Movement::Accelerating,
-            bubbles: 25,
+            bubbles: 25.,
+            bubble_emitter: BubbleEmitter::default(),
             speed: vec2(15., 7.),
             speed_randomness: vec2(0.5, 0.5),
             collision_aversion: 0.90,
@@ -39,6 +59,14 @@ impl Default for FishConfig {
                 w: 90.,
                 h: 52.5,
             },
+            anchor_range: 10.,
+            depth: 0.,
+            appetite: 0.,
+            palette: HashMap::from([
+                ("ok".to_string(), "white".to_string()),
+                ("warn".to_string(), "orange".to_string()),
+                ("critical".to_string(), "red".to_string()),
+            ]),
         }
     }
 }
@@ -56,7 +84,70 @@ impl FishConfig {
         self.speed - self.speed * random_speed
     }
 
+    pub fn frame_size(&self, texture: &Texture2D) -> Vec2 {
+        self.animation
+            .map_or(texture.size(), |animation| animation.frame_size(texture))
+    }
+
+    pub fn tint(&self, fish_data: &FishData) -> Color {
+        fish_data
+            .color
+            .as_ref()
+            .or_else(|| {
+                fish_data
+                    .status
+                    .as_ref()
+                    .and_then(|status| self.palette.get(status))
+            })
+            .and_then(|color| parse_color(color))
+            .unwrap_or(WHITE)
+    }
+
+    pub fn bubble_amount(&self, multiplier: f32) -> u32 {
+        (self.bubbles * multiplier).round().max(0.) as u32
+    }
+
     pub fn randomized_bubble_amount(&self) -> u32 {
-        gen_range(0, 25)
+        self.bubble_amount(gen_range(0., 1.))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(bubbles: f32) -> FishConfig {
+        FishConfig {
+            bubbles,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn bubble_amount_scales_configured_rate() {
+        assert_eq!(config(25.).bubble_amount(1.), 25);
+        assert_eq!(config(25.).bubble_amount(2.), 50);
+        assert_eq!(config(10.).bubble_amount(0.), 0);
+    }
+
+    #[test]
+    fn bubble_amount_keeps_fractional_multipliers() {
+        assert_eq!(config(25.).bubble_amount(0.5), 13);
+        assert_eq!(config(10.).bubble_amount(0.25), 3);
+        assert_eq!(config(2.5).bubble_amount(1.5), 4);
+    }
+
+    #[test]
+    fn bubble_amount_never_negative() {
+        assert_eq!(config(25.).bubble_amount(-1.), 0);
+        assert_eq!(config(-5.).bubble_amount(1.), 0);
+    }
+
+    #[test]
+    fn randomized_bubble_amount_respects_configured_rate() {
+        for _ in 0..100 {
+            assert!(config(4.).randomized_bubble_amount() <= 4);
+        }
+        assert_eq!(config(0.).randomized_bubble_amount(), 0);
     }
 }
@@ -1,21 +1,83 @@
+#![allow(clippy::question_mark)]
+
 use nanoserde::{DeJson, SerJson};
+use std::collections::HashMap;
 
-#[derive(Clone, DeJson, SerJson)]
+#[derive(Clone, PartialEq, DeJson, SerJson)]
 #[nserde(default)]
 pub struct FishData {
+    pub id: Option<String>,
     pub fish: String,
     pub size: f32,
     pub speed: f32,
     pub bubbles: f32,
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub zone: Option<String>,
+    pub depth: Option<f32>,
+    pub color: Option<String>,
+    pub status: Option<String>,
+    pub label: Option<String>,
+    pub highlight: bool,
+    pub ttl: Option<f32>,
+    pub meta: HashMap<String, String>,
 }
 
 impl Default for FishData {
     fn default() -> FishData {
         FishData {
+            id: None,
             fish: "clownfish".to_string(),
             size: 1.0,
             speed: 1.0,
             bubbles: 1.0,
+            x: None,
+            y: None,
+            zone: None,
+            depth: None,
+            color: None,
+            status: None,
+            label: None,
+            highlight: false,
+            ttl: None,
+            meta: HashMap::new(),
+        }
+    }
+}
+
+impl FishData {
+    pub fn fields(&self) -> Vec<(String, String)> {
+        let mut fields = vec![
+            ("fish".to_string(), self.fish.clone()),
+            ("size".to_string(), self.size.to_string()),
+            ("speed".to_string(), self.speed.to_string()),
+            ("bubbles".to_string(), self.bubbles.to_string()),
+        ];
+        let optional_fields = [
+            ("id", self.id.clone()),
+            ("label", self.label.clone()),
+            ("status", self.status.clone()),
+            ("color", self.color.clone()),
+            ("zone", self.zone.clone()),
+            ("x", self.x.map(|x| x.to_string())),
+            ("y", self.y.map(|y| y.to_string())),
+            ("depth", self.depth.map(|depth| depth.to_string())),
+            ("ttl", self.ttl.map(|ttl| ttl.to_string())),
+        ];
+        for (key, value) in optional_fields {
+            if let Some(value) = value {
+                fields.push((key.to_string(), value));
+            }
+        }
+        if self.highlight {
+            fields.push(("highlight".to_string(), "true".to_string()));
         }
+        let mut meta = self.meta.iter().collect::<Vec<_>>();
+        meta.sort();
+        fields.extend(
+            meta.into_iter()
+                .map(|(key, value)| (key.to_owned(), value.to_owned())),
+        );
+        fields
     }
 }
@@ -1,30 +1,64 @@
+#[cfg(not(target_arch = "wasm32"))]
+use crate::input_stream::InputStream;
 use crate::{
-    config::Config, fish::Fish, fish_config::FishConfig, fish_data::FishData,
-    input_data::InputData, resources::Resources, scene_config::SceneConfig, scenes::Scenes,
-    show_legend::ShowLegend,
+    config::Config, data_source::DataSource, data_status::DataStatus, decoration::Decoration,
+    decoration_config::DecorationConfig, effect::Effect, event_data::EventData,
+    event_kind::EventKind, fish::Fish, fish_config::FishConfig, fish_data::FishData, food::Food,
+    http_config::HttpConfig, input_data::InputData, input_data_error::InputDataError,
+    prop_value::PropValue, resources::Resources, scene_config::SceneConfig, scenes::Scenes,
+    shaders::fish_wiggle, show_diagnostics::ShowDiagnostics, show_inspection::ShowInspection,
+    show_legend::ShowLegend, tank_state::TankState, tint::parse_color,
 };
 use macroquad::{
+    color::colors::WHITE,
     experimental::{
         collections::storage,
         coroutines::{Coroutine, start_coroutine},
     },
-    math::{Rect, Vec2},
-    rand::ChooseRandom,
+    input::mouse_position,
+    material::Material,
+    math::{Rect, Vec2, vec2},
+    prelude::error,
+    rand::{ChooseRandom, gen_range},
     texture::Texture2D,
+    window::{screen_height, screen_width},
 };
-use std::collections::HashMap;
+use macroquad_particles::EmitterConfig;
+use std::collections::{HashMap, HashSet};
+
+/// New data loaded from a source, or nothing if it hasn't changed.
+type Loaded = Result<Option<InputData>, InputDataError>;
 
 pub struct FishTank {
     fishes: Vec<Fish>,
     fish_configs: HashMap<String, FishConfig>,
     fish_keys: Vec<String>,
     school: Vec<FishData>,
+    props: HashMap<String, PropValue>,
     bubble_texture: Option<Texture2D>,
+    bubble_textures: HashMap<String, Texture2D>,
     fish_textures: HashMap<String, Texture2D>,
+    decoration_textures: HashMap<String, Texture2D>,
+    decorations: Vec<Decoration>,
+    effects: Vec<Effect>,
+    food: Vec<Food>,
+    pending_events: Vec<EventData>,
+    wiggle_material: Option<Material>,
     scenes: Scenes,
-    reloader: Option<Coroutine>,
+    reloader: Option<Coroutine<(String, Loaded)>>,
+    sources: HashMap<String, DataSource>,
+    shown_source: String,
+    stale_after: f32,
+    data_warnings: Vec<String>,
+    http: HttpConfig,
+    #[cfg(not(target_arch = "wasm32"))]
+    input_streams: HashMap<String, InputStream>,
     pub loaded: bool,
     show_legend: ShowLegend,
+    show_inspection: ShowInspection,
+    show_diagnostics: ShowDiagnostics,
+    selected_fish: Option<usize>,
+    selected_type: Option<String>,
 }
 
 impl Default for FishTank {
@@ -34,29 +68,63 @@ impl Default for FishTank {
 }
 
 impl FishTank {
+    const FOOD_FLAKES: usize = 6;
+    const FOOD_SPREAD: f32 = 3.;
+    const STALE_AFTER: f32 = 300.;
+    const POSTED_SOURCE: &'static str = "POST /inputdata";
+
     pub fn new() -> Self {
         Self {
             fishes: vec![],
             fish_keys: vec![],
             fish_configs: HashMap::new(),
             school: vec![],
+            props: HashMap::new(),
             bubble_texture: None,
+            bubble_textures: HashMap::new(),
             fish_textures: HashMap::new(),
+            decoration_textures: HashMap::new(),
+            decorations: vec![],
+            effects: vec![],
+            food: vec![],
+            pending_events: vec![],
+            wiggle_material: None,
             scenes: Scenes::empty(),
             reloader: None,
+            sources: HashMap::new(),
+            shown_source: String::new(),
+            stale_after: Self::STALE_AFTER,
+            data_warnings: vec![],
+            http: HttpConfig::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            input_streams: HashMap::new(),
             loaded: false,
             show_legend: ShowLegend::empty(),
+            show_inspection: ShowInspection::new(),
+            show_diagnostics: ShowDiagnostics::new(),
+            selected_fish: None,
+            selected_type: None,
         }
     }
 
     pub fn add_resources(&mut self) {
         let resources = storage::get::<Resources>();
-        storage::store(resources.input_data.clone());
         self.bubble_texture = Some(resources.bubble_texture.clone());
-        self.fish_keys = Vec::from_iter(resources.config.fishes.keys().cloned());
+        self.bubble_textures = resources.bubble_textures.clone();
         self.fish_configs = resources.config.fishes.clone();
-        self.school = (*resources.input_data.school).to_vec();
+        self.http = resources.config.http.clone();
+        self.stale_after = resources.config.stale_after.unwrap_or(Self::STALE_AFTER);
+        let path = resources.config.input_data_path.clone().unwrap_or_default();
+        let source = self.source(&path);
+        if let Some(err) = resources.input_data_error.clone() {
+            source.status.failed(err);
+        } else if let Some(input_data) = resources.input_data.clone() {
+            source.loaded(input_data);
+        }
+        self.fish_keys = self.sorted_fish_keys();
         self.fish_textures = resources.fish_textures.clone();
+        self.decoration_textures = resources.decoration_textures.clone();
+        self.wiggle_material = fish_wiggle::material().ok();
         let scenes = resources.config.scenes.clone().unwrap_or_else(|| {
             vec![SceneConfig::new(
                 resources.config.input_data_path.clone(),
@@ -64,31 +132,104 @@ impl FishTank {
             )]
         });
         self.scenes = Scenes::new(scenes, (*resources.backgrounds).to_vec());
-        self.show_legend = ShowLegend::new(resources.input_data.legend.clone());
-        self.populate();
+        self.decorate();
+        match resources
+            .input_data
+            .clone()
+            .filter(|_| self.scene_source() == path)
+        {
+            Some(input_data) => {
+                self.shown_source = path;
+                self.show_new_data(input_data);
+            }
+            None => self.reload_data(),
+        }
         self.loaded = true;
     }
 
+    /// Shows what was loaded for the scene before while loading it again.
     pub fn reload_data(&mut self) {
+        self.show_scene_data(false);
+        self.load_scene_data();
+    }
+
+    fn load_scene_data(&mut self) {
         if let Some(path) = self.scenes.input_data_path() {
+            #[cfg(not(target_arch = "wasm32"))]
+            if self.open_input_stream(path.clone()) {
+                return;
+            }
+            let source = self.scene_source();
+            let loaded = self
+                .sources
+                .get(&source)
+                .is_some_and(|source| source.input_data.is_some());
+            let mut http = self.http.clone();
+            http.headers.extend(self.scenes.headers().clone());
             self.reloader = Some(start_coroutine(async move {
-                let data = InputData::load(path).await;
-                storage::store(data);
+                let result = InputData::reload(path, http)
+                    .await
+                    .map(|(data, modified)| Some(data).filter(|_| modified || !loaded));
+                (source, result)
             }));
         }
     }
 
     pub fn update_config(&mut self, config: Config) {
         self.fish_configs = config.fishes;
+        self.http = config.http;
+        self.fish_keys = self.sorted_fish_keys();
+        self.data_warnings = storage::get::<InputData>().validate(&self.fish_configs);
+        self.selected_type = self
+            .selected_type
+            .take()
+            .filter(|fish_type| self.fish_configs.contains_key(fish_type));
         self.repopulate();
     }
 
     pub fn next_scene(&mut self) {
-        if self.show_legend.showing {
-            self.show_legend.hide();
-        }
         self.scenes.next();
-        self.reload_data();
+        self.change_scene();
+    }
+
+    pub fn show_scene(&mut self, index: usize) -> bool {
+        if !self.scenes.select(index) {
+            return false;
+        }
+        self.change_scene();
+        true
+    }
+
+    /// Posted data is shown until the scene changes or the data is reloaded.
+    pub fn post_input_data(&mut self, input_data: InputData) {
+        self.shown_source = Self::POSTED_SOURCE.to_string();
+        self.source(Self::POSTED_SOURCE).loaded(input_data.clone());
+        self.show_new_data(input_data);
+    }
+
+    pub fn state(&self) -> TankState {
+        let mut fishes = HashMap::new();
+        for fish_type in self
+            .fishes
+            .iter()
+            .filter(|fish| !fish.is_dying())
+            .filter_map(|fish| fish.fish_type())
+        {
+            *fishes.entry(fish_type.to_string()).or_insert(0) += 1;
+        }
+        TankState {
+            scene: self.scenes.current(),
+            scenes: self.scenes.count(),
+            switching_scenes: self.scenes.is_switching(),
+            legend: self.show_legend.showing,
+            fishes,
+            stale: self.shown_status().is_some_and(DataStatus::is_stale),
+            error: self
+                .shown_status()
+                .and_then(DataStatus::last_error)
+                .map(|err| err.to_string()),
+            ..Default::default()
+        }
     }
 
     pub fn toggle_switching_scenes(&mut self) -> bool {
@@ -99,55 +240,320 @@ impl FishTank {
         self.show_legend.toggle_show(self.scenes.legend());
     }
 
-    pub fn tick(&mut self, delta: f32) {
+    pub fn toggle_inspection(&mut self) -> bool {
+        self.selected_fish = None;
+        self.show_inspection.toggle_show()
+    }
+
+    pub fn toggle_diagnostics(&mut self) -> bool {
+        self.show_diagnostics.toggle_show()
+    }
+
+    pub fn is_inspecting(&self) -> bool {
+        self.show_inspection.showing
+    }
+
+    pub fn select_fish(&mut self, rect: Vec2) {
+        self.selected_fish = self.fish_at(Self::tank_mouse_position(rect));
+    }
+
+    pub fn feed(&mut self, x: Option<f32>) {
+        self.pending_events.push(EventData {
+            kind: EventKind::Feed,
+            x,
+            ..Default::default()
+        });
+    }
+
+    pub fn feed_at_mouse(&mut self, rect: Vec2) {
+        self.feed(Some(Self::tank_mouse_position(rect).x / rect.x));
+    }
+
+    pub fn tick(&mut self, delta: f32, rect: Vec2) {
         self.tick_data_reloading(delta);
+        self.trigger_events(rect);
         let collision_boxes = self
             .fishes
             .iter()
             .map(|fish| fish.collision_box())
             .collect::<Vec<Rect>>();
+        let food = self
+            .food
+            .iter()
+            .map(|food| food.position())
+            .collect::<Vec<Vec2>>();
         for fish in self.fishes.iter_mut() {
-            fish.tick(delta, &collision_boxes);
+            fish.tick(delta, &collision_boxes, &food);
+        }
+        self.remove_dead_fishes();
+        self.tick_food(delta, rect);
+        for decoration in self.decorations.iter_mut() {
+            decoration.tick(delta);
         }
+        for effect in self.effects.iter_mut() {
+            effect.tick(delta);
+        }
+        self.effects.retain(|effect| !effect.is_finished());
     }
 
     pub fn draw(&mut self, rect: Vec2) {
         self.scenes.draw(rect);
-        for fish in self.fishes.iter_mut() {
+        let mut layers = self.scenes.layers().into_iter().peekable();
+        for index in self.draw_order() {
+            let fish = &mut self.fishes[index];
+            while let Some(layer) = layers.next_if(|layer| layer.depth >= fish.depth()) {
+                self.scenes.draw_layer(layer, rect);
+            }
             fish.draw();
         }
+        for layer in layers {
+            self.scenes.draw_layer(layer, rect);
+        }
+        for food in self.food.iter() {
+            food.draw();
+        }
+        for decoration in self.decorations.iter_mut() {
+            decoration.draw();
+        }
+        for effect in self.effects.iter_mut() {
+            effect.draw(rect);
+        }
+        if let Some(status) = self.shown_status() {
+            status.draw(rect);
+        }
+        self.draw_labels(rect);
     }
 
     pub fn draw_legend(&self) {
         self.show_legend.draw();
     }
 
+    pub fn draw_inspection(&self, rect: Vec2) {
+        if !self.show_inspection.showing {
+            return;
+        }
+        let fish = self
+            .selected_fish
+            .or_else(|| self.fish_at(Self::tank_mouse_position(rect)))
+            .and_then(|index| self.fishes.get(index));
+        if let Some(fish) = fish {
+            self.show_inspection.draw(&fish.inspection());
+        }
+    }
+
+    pub fn draw_diagnostics(&self) {
+        if !self.show_diagnostics.showing {
+            return;
+        }
+        let path = if self.shows_posted_data() {
+            Self::POSTED_SOURCE.to_string()
+        } else {
+            self.scenes.input_data_path().unwrap_or_default()
+        };
+        let status = self.shown_status();
+        let error = status.and_then(DataStatus::last_error);
+        let mut lines = vec![
+            format!("Input data: {}", path),
+            match status.filter(|status| status.has_loaded()) {
+                Some(status) => format!("Last loaded: {:.0} s ago", status.age()),
+                None => "Last loaded: never".to_string(),
+            },
+        ];
+        if let Some(err) = error {
+            lines.push(err.to_string());
+        }
+        if self.data_warnings.is_empty() && error.is_none() {
+            lines.push("No problems".to_string());
+        }
+        lines.extend(self.data_warnings.iter().cloned());
+        self.show_diagnostics.draw(&lines);
+    }
+
     pub fn repopulate(&mut self) {
-        self.reset();
+        for fish in self.fishes.iter_mut() {
+            fish.die();
+        }
         self.populate();
     }
 
-    pub fn add_fish(&mut self) {
-        let fish = self.random_fish();
-        self.fishes.push(fish);
+    pub fn select_fish_type(&mut self, index: Option<usize>) -> Option<&str> {
+        self.selected_type = index.and_then(|index| self.fish_keys.get(index).cloned());
+        self.selected_type.as_deref()
+    }
+
+    pub fn add_fish(&mut self) -> Result<(), &'static str> {
+        let fish_type = self.selected_type.clone();
+        self.add_fishes(fish_type.as_deref(), 1)
+    }
+
+    pub fn remove_fish(&mut self) -> Result<(), &'static str> {
+        let fish_type = self.selected_type.clone();
+        self.remove_fishes(fish_type.as_deref(), 1)
+    }
+
+    pub fn add_fishes(
+        &mut self,
+        fish_type: Option<&str>,
+        count: usize,
+    ) -> Result<(), &'static str> {
+        for _ in 0..count {
+            let fish_key = match fish_type {
+                Some(fish_type) => fish_type.to_string(),
+                None => self.fish_keys.choose().ok_or("FishConfig missing")?.clone(),
+            };
+            let fish = self.random_fish(&fish_key)?;
+            self.fishes.push(fish);
+        }
+        Ok(())
+    }
+
+    pub fn remove_fishes(
+        &mut self,
+        fish_type: Option<&str>,
+        count: usize,
+    ) -> Result<(), &'static str> {
+        if fish_type.is_some_and(|fish_type| !self.fish_configs.contains_key(fish_type)) {
+            return Err("FishConfig missing");
+        }
+        for fish in self
+            .fishes
+            .iter_mut()
+            .rev()
+            .filter(|fish| !fish.is_dying())
+            .filter(|fish| fish_type.is_none_or(|fish_type| fish.fish_type() == Some(fish_type)))
+            .take(count)
+        {
+            fish.die();
+        }
+        Ok(())
+    }
+
+    /// Updates the fish with the same id, or adds it if there is no such fish.
+    pub fn update_fish(&mut self, fish_data: FishData) -> Result<(), &'static str> {
+        let id = fish_data.id.as_deref().ok_or("Fish id missing")?;
+        let mut fish = self.create_school_fish(id, &fish_data)?;
+        match self.fish_with_id(id) {
+            Some(index) => {
+                fish.take_place_of(&self.fishes[index]);
+                self.fishes[index] = fish;
+            }
+            None => self.fishes.push(fish),
+        }
+        Ok(())
+    }
+
+    pub fn remove_fish_with_id(&mut self, id: &str) -> bool {
+        match self.fish_with_id(id) {
+            Some(index) => {
+                self.fishes[index].die();
+                true
+            }
+            None => false,
+        }
     }
 
-    pub fn remove_fish(&mut self) {
-        if !self.fishes.is_empty() {
-            self.fishes.pop();
+    fn draw_labels(&self, rect: Vec2) {
+        let label_mode = self.scenes.label_mode();
+        let mouse_position = Self::tank_mouse_position(rect);
+        for fish in self.fishes.iter() {
+            let hovered = fish.collision_box().contains(mouse_position);
+            if label_mode.show_label(fish.is_highlighted(), hovered) {
+                fish.draw_label();
+            }
         }
     }
 
+    fn draw_order(&self) -> Vec<usize> {
+        let mut order = (0..self.fishes.len()).collect::<Vec<usize>>();
+        order.sort_by(|a, b| self.fishes[*b].depth().total_cmp(&self.fishes[*a].depth()));
+        order
+    }
+
+    fn fish_at(&self, position: Vec2) -> Option<usize> {
+        self.draw_order()
+            .into_iter()
+            .rev()
+            .find(|index| self.fishes[*index].collision_box().contains(position))
+    }
+
+    fn tank_mouse_position(rect: Vec2) -> Vec2 {
+        let (x, y) = mouse_position();
+        vec2(x / screen_width() * rect.x, y / screen_height() * rect.y)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn open_input_stream(&mut self, path: String) -> bool {
+        if !InputStream::is_stream(&path) {
+            return false;
+        }
+        // Streams are kept open while showing other scenes, since stdin can only be read once
+        self.input_streams
+            .entry(path.clone())
+            .or_insert_with(|| InputStream::open(path));
+        true
+    }
+
+    /// Every stream is read, also while showing other scenes, so the latest
+    /// data is kept for its source and shown as soon as its scene comes back.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn tick_input_streams(&mut self) {
+        let mut received = vec![];
+        for (path, stream) in self.input_streams.iter() {
+            // Each line is a complete tank, so only the latest one needs to be shown
+            let mut latest = None;
+            let mut failure = None;
+            for result in stream.data() {
+                match result {
+                    Ok(input_data) => {
+                        latest = Some(input_data);
+                        failure = None;
+                    }
+                    Err(err) => {
+                        error!("{}", err);
+                        failure = Some(err);
+                    }
+                }
+            }
+            if let Some(input_data) = latest {
+                received.push((path.clone(), Ok(Some(input_data))));
+            }
+            if let Some(err) = failure {
+                received.push((path.clone(), Err(err)));
+            }
+        }
+        for (path, result) in received {
+            self.receive_data(&path, result);
+        }
+    }
+
+    /// Everything that depends on the scene is rebuilt, also when the new scene
+    /// shows the same data as the previous one.
+    fn change_scene(&mut self) {
+        if self.show_legend.showing {
+            self.show_legend.hide();
+        }
+        self.decorate();
+        self.show_scene_data(true);
+        self.load_scene_data();
+    }
+
     fn tick_data_reloading(&mut self, delta: f32) {
-        if let Some(reloader) = self.reloader {
-            if reloader.is_done() {
-                self.update_data();
-                self.reloader = None;
-            } else {
+        #[cfg(not(target_arch = "wasm32"))]
+        self.tick_input_streams();
+        if let Some(reloader) = &self.reloader {
+            if !reloader.is_done() {
                 return;
             }
+            let result = reloader.retrieve();
+            self.reloader = None;
+            if let Some((source, result)) = result {
+                if let Err(err) = &result {
+                    error!("{}", err);
+                }
+                self.receive_data(&source, result);
+            }
         }
-        if !self.scenes.is_switching() {
+        if !self.scenes.is_switching() || self.shows_posted_data() {
             return;
         }
         self.scenes.tick(delta);
@@ -156,64 +562,450 @@ impl FishTank {
         }
     }
 
-    fn update_data(&mut self) {
+    /// Keeps what was loaded for the source, and shows new data if the source
+    /// is shown.
+    fn receive_data(&mut self, source: &str, result: Loaded) {
+        match result {
+            Ok(Some(input_data)) => {
+                self.source(source).loaded(input_data.clone());
+                if source == self.shown_source {
+                    self.show_new_data(input_data);
+                }
+            }
+            Ok(None) => self.source(source).status.succeeded(),
+            Err(err) => self.source(source).status.failed(err),
+        }
+    }
+
+    fn source(&mut self, source: &str) -> &mut DataSource {
+        let stale_after = self.stale_after;
+        self.sources
+            .entry(source.to_string())
+            .or_insert_with(|| DataSource::new(stale_after))
+    }
+
+    fn shown_status(&self) -> Option<&DataStatus> {
+        self.sources
+            .get(&self.shown_source)
+            .map(|source| &source.status)
+    }
+
+    fn shows_posted_data(&self) -> bool {
+        self.shown_source == Self::POSTED_SOURCE
+    }
+
+    /// Scenes share what was loaded when they load the same input data with
+    /// the same headers, since other headers can give other data.
+    fn scene_source(&self) -> String {
+        let path = self.scenes.input_data_path().unwrap_or_default();
+        #[cfg(not(target_arch = "wasm32"))]
+        if InputStream::is_stream(&path) {
+            return path;
+        }
+        let mut headers = self.scenes.headers().iter().collect::<Vec<_>>();
+        if headers.is_empty() {
+            return path;
+        }
+        headers.sort();
+        format!("{} {:?}", path, headers)
+    }
+
+    /// Shows the data last loaded for the scene, or an empty tank if nothing
+    /// has been loaded for it yet, so another scene's fishes are not kept.
+    /// Only events with a time that haven't been triggered yet are triggered.
+    fn show_scene_data(&mut self, rebuild: bool) {
+        let source = self.scene_source();
+        let input_data = self.source(&source).input_data.clone().unwrap_or_default();
+        let events = input_data
+            .events
+            .iter()
+            .filter(|event| event.key().is_some())
+            .cloned()
+            .collect::<Vec<_>>();
+        self.shown_source = source;
+        storage::store(input_data);
+        self.update_data(rebuild);
+        self.queue_events(&events);
+    }
+
+    fn show_new_data(&mut self, input_data: InputData) {
+        let events = input_data.events.clone();
+        storage::store(input_data);
+        self.update_data(false);
+        self.queue_events(&events);
+    }
+
+    fn update_data(&mut self, rebuild: bool) {
         let input_data = storage::get_mut::<InputData>();
         self.school = (*input_data.school).to_vec();
+        self.props = input_data.props.clone();
+        self.data_warnings = input_data.validate(&self.fish_configs);
         self.show_legend = ShowLegend::new(input_data.legend.clone());
-        self.repopulate();
+        self.reconcile(rebuild);
+    }
+
+    /// Events are remembered per source, so they aren't triggered again when
+    /// its scene comes back.
+    fn queue_events(&mut self, events: &[EventData]) {
+        let shown_source = self.shown_source.clone();
+        let seen_events = &mut self.source(&shown_source).seen_events;
+        // Forget events that are gone from the data, so the set doesn't keep growing
+        let keys = events
+            .iter()
+            .filter_map(EventData::key)
+            .collect::<HashSet<String>>();
+        seen_events.retain(|key| keys.contains(key));
+        let unseen = events
+            .iter()
+            .filter(|event| event.key().is_none_or(|key| seen_events.insert(key)))
+            .cloned()
+            .collect::<Vec<_>>();
+        self.pending_events.extend(unseen);
+    }
+
+    fn trigger_events(&mut self, rect: Vec2) {
+        for event in std::mem::take(&mut self.pending_events) {
+            self.trigger_event(&event, rect);
+        }
+    }
+
+    fn trigger_event(&mut self, event: &EventData, rect: Vec2) {
+        let bubble_texture = self.bubble_texture.clone().unwrap();
+        match event.kind {
+            EventKind::Burst => {
+                if let Some(index) = self.event_fish(event) {
+                    let position = self.fishes[index].collision_box().center();
+                    self.effects.push(Effect::burst(position, bubble_texture));
+                }
+            }
+            EventKind::Dart => {
+                if let Some(index) = self.event_fish(event) {
+                    self.fishes[index].dart();
+                }
+            }
+            EventKind::Flash => {
+                let color = event
+                    .color
+                    .as_deref()
+                    .and_then(parse_color)
+                    .unwrap_or(WHITE);
+                self.effects.push(Effect::flash(color));
+            }
+            EventKind::Splash => {
+                self.effects
+                    .push(Effect::splash(Self::event_x(event, rect), bubble_texture));
+            }
+            EventKind::Feed => {
+                let x = Self::event_x(event, rect);
+                for _ in 0..Self::FOOD_FLAKES {
+                    self.food.push(Food::new(vec2(
+                        x + gen_range(-Self::FOOD_SPREAD, Self::FOOD_SPREAD),
+                        gen_range(0., Self::FOOD_SPREAD),
+                    )));
+                }
+            }
+        }
+    }
+
+    fn event_x(event: &EventData, rect: Vec2) -> f32 {
+        event.x.unwrap_or_else(|| gen_range(0., 1.)).clamp(0., 1.) * rect.x
+    }
+
+    fn remove_dead_fishes(&mut self) {
+        self.remember_expired_fishes();
+        self.selected_fish = self
+            .selected_fish
+            .filter(|index| !self.fishes[*index].is_dead())
+            .map(|index| {
+                index
+                    - self.fishes[..index]
+                        .iter()
+                        .filter(|fish| fish.is_dead())
+                        .count()
+            });
+        self.fishes.retain(|fish| !fish.is_dead());
+    }
+
+    fn tick_food(&mut self, delta: f32, rect: Vec2) {
+        for food in self.food.iter_mut() {
+            food.tick(delta, rect.y);
+            let position = food.position();
+            if self.fishes.iter().any(|fish| fish.can_eat(position)) {
+                food.eat();
+            }
+        }
+        self.food.retain(|food| !food.is_gone());
+    }
+
+    fn event_fish(&self, event: &EventData) -> Option<usize> {
+        let candidates = (0..self.fishes.len())
+            .filter(|index| {
+                event
+                    .fish
+                    .as_ref()
+                    .is_none_or(|fish| self.fishes[*index].fish_type() == Some(fish))
+                    && !self.fishes[*index].is_dying()
+            })
+            .collect::<Vec<usize>>();
+        candidates.choose().copied()
     }
 
     fn populate(&mut self) {
-        for fish_data in self.school.iter() {
-            if let Ok(fish) = self.create_fish(fish_data) {
+        let expired = self.shown_expired_fishes();
+        for (key, fish_data) in self.school_keys().into_iter().zip(self.school.clone()) {
+            if expired.contains(&key) {
+                continue;
+            }
+            if let Ok(fish) = self.create_school_fish(&key, &fish_data) {
                 self.fishes.push(fish);
             }
         }
     }
 
-    fn reset(&mut self) {
-        self.fishes.clear();
+    /// Updates the fishes to match the school, keeping the age and position of
+    /// fishes that are still in it, and letting the others die. Rebuilding
+    /// also replaces unchanged fishes, to move them into the zones of a new
+    /// scene.
+    fn reconcile(&mut self, rebuild: bool) {
+        self.remember_expired_fishes();
+        let source = self.shown_source.clone();
+        let expired = self.shown_expired_fishes();
+        let keys = self.school_keys();
+        let mut current = HashSet::new();
+        for (key, fish_data) in keys.iter().zip(self.school.clone()) {
+            let existing = self.fishes.iter().position(|fish| {
+                !fish.is_dying() && fish.key() == Some((source.as_str(), key.as_str()))
+            });
+            if let Some(index) =
+                existing.filter(|index| !rebuild && self.fishes[*index].data() == Some(&fish_data))
+            {
+                current.insert(index);
+                continue;
+            }
+            if existing.is_none() && expired.contains(key) {
+                continue;
+            }
+            let Ok(mut fish) = self.create_school_fish(key, &fish_data) else {
+                continue;
+            };
+            match existing {
+                Some(index) => {
+                    fish.take_place_of(&self.fishes[index]);
+                    self.fishes[index] = fish;
+                    current.insert(index);
+                }
+                None => {
+                    current.insert(self.fishes.len());
+                    self.fishes.push(fish);
+                }
+            }
+        }
+        for (index, fish) in self.fishes.iter_mut().enumerate() {
+            if !current.contains(&index) {
+                fish.die();
+            }
+        }
+        // Only the fishes gone from the source's own data are forgotten
+        self.source(&source)
+            .expired_fishes
+            .retain(|key| keys.contains(key));
+        self.update_decorations();
+    }
+
+    /// Fishes are keyed by id, or by type and order when they have no id.
+    fn school_keys(&self) -> Vec<String> {
+        let mut counts = HashMap::new();
+        self.school
+            .iter()
+            .map(|fish_data| match &fish_data.id {
+                Some(id) => id.clone(),
+                None => {
+                    let count = counts.entry(&fish_data.fish).or_insert(0);
+                    *count += 1;
+                    format!("{}#{}", fish_data.fish, count)
+                }
+            })
+            .collect()
+    }
+
+    /// Fishes that died of old age are not brought back while they remain in
+    /// the school of their source.
+    fn remember_expired_fishes(&mut self) {
+        for (source, key) in self
+            .fishes
+            .iter()
+            .filter(|fish| fish.is_expired())
+            .filter_map(Fish::key)
+        {
+            if let Some(expired) = self
+                .sources
+                .get_mut(source)
+                .map(|source| &mut source.expired_fishes)
+                .filter(|expired| !expired.contains(key))
+            {
+                expired.insert(key.to_string());
+            }
+        }
     }
 
-    fn random_fish_config(&self) -> &FishConfig {
-        let fish_key = self.fish_keys.choose().unwrap();
-        return self.fish_configs.get(fish_key).unwrap();
+    fn shown_expired_fishes(&self) -> HashSet<String> {
+        self.sources
+            .get(&self.shown_source)
+            .map(|source| source.expired_fishes.clone())
+            .unwrap_or_default()
     }
 
-    fn random_fish(&self) -> Fish {
-        let fish_config = self.random_fish_config();
-        Fish::new(
+    /// Decorations are created for each scene, and only change state with the data.
+    fn decorate(&mut self) {
+        self.decorations = self
+            .scenes
+            .decorations()
+            .iter()
+            .map(|config| self.create_decoration(config))
+            .collect();
+    }
+
+    fn update_decorations(&mut self) {
+        for (decoration, config) in self.decorations.iter_mut().zip(self.scenes.decorations()) {
+            decoration.set_state(Self::decoration_state(config, &self.props, &self.school));
+        }
+    }
+
+    fn sorted_fish_keys(&self) -> Vec<String> {
+        let mut fish_keys = Vec::from_iter(self.fish_configs.keys().cloned());
+        fish_keys.sort();
+        fish_keys
+    }
+
+    fn fish_with_id(&self, id: &str) -> Option<usize> {
+        self.fishes
+            .iter()
+            .position(|fish| !fish.is_dying() && fish.id() == Some(id))
+    }
+
+    fn random_fish(&self, fish_key: &str) -> Result<Fish, &'static str> {
+        let fish_config = self
+            .fish_configs
+            .get(fish_key)
+            .ok_or("FishConfig missing")?;
+        Ok(self.new_fish(
+            fish_key,
+            fish_config,
             fish_config.randomized_size(),
             fish_config.randomized_speed(),
-            fish_config.collision_aversion,
             fish_config.area,
-            fish_config.movement,
+            fish_config.depth,
+            fish_config.randomized_bubble_amount(),
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn new_fish(
+        &self,
+        fish_key: &str,
+        fish_config: &FishConfig,
+        size: f32,
+        speed: Vec2,
+        area: Rect,
+        depth: f32,
+        bubble_amount: u32,
+    ) -> Fish {
+        let mut fish = Fish::new(
+            fish_key,
+            fish_config,
+            size,
+            speed,
+            area,
+            depth,
             self.fish_textures
                 .get(&fish_config.texture)
                 .unwrap()
                 .clone(),
+            self.emitter_config(fish_config, bubble_amount),
+        );
+        if let Some(material) = &self.wiggle_material {
+            fish.set_wiggle_material(material.clone());
+        }
+        fish
+    }
+
+    fn emitter_config(&self, fish_config: &FishConfig, amount: u32) -> EmitterConfig {
+        let texture = fish_config
+            .bubble_emitter
+            .texture
+            .as_ref()
+            .and_then(|texture| self.bubble_textures.get(texture))
+            .or(self.bubble_texture.as_ref())
+            .unwrap()
+            .clone();
+        fish_config.bubble_emitter.emitter_config(amount, texture)
+    }
+
+    fn fish_area(&self, fish_config: &FishConfig, fish_data: &FishData) -> Rect {
+        fish_data
+            .zone
+            .as_ref()
+            .and_then(|name| self.scenes.zone(name))
+            .map_or(fish_config.area, |zone| zone.constrain(fish_config.area))
+    }
+
+    fn decoration_state(
+        config: &DecorationConfig,
+        props: &HashMap<String, PropValue>,
+        school: &[FishData],
+    ) -> PropValue {
+        config
+            .prop
+            .as_ref()
+            .and_then(|prop| props.get(prop).copied())
+            .unwrap_or_else(|| {
+                PropValue::Active(
+                    config
+                        .active_when
+                        .as_ref()
+                        .is_none_or(|fish| school.iter().any(|fish_data| &fish_data.fish == fish)),
+                )
+            })
+    }
+
+    fn create_decoration(&self, config: &DecorationConfig) -> Decoration {
+        let state = Self::decoration_state(config, &self.props, &self.school);
+        Decoration::new(
+            config,
+            config
+                .texture
+                .as_ref()
+                .and_then(|texture| self.decoration_textures.get(texture))
+                .cloned(),
             self.bubble_texture.clone().unwrap(),
-            fish_config.randomized_bubble_amount(),
+            state,
         )
     }
 
+    fn create_school_fish(&self, key: &str, fish_data: &FishData) -> Result<Fish, &'static str> {
+        let mut fish = self.create_fish(fish_data)?;
+        fish.set_key(&self.shown_source, key);
+        Ok(fish)
+    }
+
     fn create_fish(&self, fish_data: &FishData) -> Result<Fish, &'static str> {
         let fish_config = self
             .fish_configs
             .get(&fish_data.fish)
             .ok_or("FishConfig missing")?;
-        Ok(Fish::new(
+        let mut fish = self.new_fish(
+            &fish_data.fish,
+            fish_config,
             fish_config.size * fish_data.size,
             fish_config.speed * fish_data.speed,
-            fish_config.collision_aversion,
-            fish_config.area,
-            fish_config.movement,
-            self.fish_textures
-                .get(&fish_config.texture)
-                .unwrap()
-                .clone(),
-            self.bubble_texture.clone().unwrap(),
-            fish_config.bubbles * fish_data.bubbles as u32,
-        ))
+            self.fish_area(fish_config, fish_data),
+            fish_data.depth.unwrap_or(fish_config.depth),
+            fish_config.bubble_amount(fish_data.bubbles),
+        );
+        fish.set_anchor(fish_data.x, fish_data.y, fish_config.anchor_range);
+        fish.set_tint(fish_config.tint(fish_data));
+        fish.set_max_age(fish_data.ttl);
+        fish.set_data(fish_data.clone());
+        Ok(fish)
     }
 }
@@ -0,0 +1,71 @@
+use macroquad::{
+    color::Color,
+    math::{Vec2, vec2},
+    rand::gen_range,
+    shapes::draw_circle,
+};
+
+pub struct Food {
+    position: Vec2,
+    sink_speed: f32,
+    sway_phase: f32,
+    time: f32,
+    eaten: bool,
+}
+
+impl Food {
+    const LIFETIME: f32 = 15.;
+    const FADE_TIME: f32 = 2.;
+    const SINK_SPEED: f32 = 3.;
+    const SINK_SPEED_RANDOMNESS: f32 = 0.5;
+    const SWAY_SPEED: f32 = 2.;
+    const SWAY_DISTANCE: f32 = 0.6;
+    const SIZE: f32 = 0.35;
+    const COLOR: Color = Color::new(0.8, 0.55, 0.3, 1.);
+
+    pub fn new(position: Vec2) -> Self {
+        Self {
+            position,
+            sink_speed: Self::SINK_SPEED
+                - Self::SINK_SPEED * gen_range(0., Self::SINK_SPEED_RANDOMNESS),
+            sway_phase: gen_range(0., std::f32::consts::TAU),
+            time: 0.,
+            eaten: false,
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32, floor: f32) {
+        self.time += delta;
+        self.position.y = (self.position.y + self.sink_speed * delta).min(floor - Self::SIZE);
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+            + vec2(
+                (self.time * Self::SWAY_SPEED + self.sway_phase).sin() * Self::SWAY_DISTANCE,
+                0.,
+            )
+    }
+
+    pub fn eat(&mut self) {
+        self.eaten = true;
+    }
+
+    pub fn is_gone(&self) -> bool {
+        self.eaten || self.time >= Self::LIFETIME
+    }
+
+    pub fn draw(&self) {
+        let fade = ((Self::LIFETIME - self.time) / Self::FADE_TIME).clamp(0., 1.);
+        let position = self.position();
+        draw_circle(
+            position.x,
+            position.y,
+            Self::SIZE,
+            Color {
+                a: Self::COLOR.a * fade,
+                ..Self::COLOR
+            },
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{http_config::HttpConfig, secret::resolve_secrets};
+use macroquad::{
+    experimental::collections::storage, prelude::error, time::get_time, window::next_frame,
+};
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    sync::mpsc::{TryRecvError, channel},
+    thread,
+    time::Duration,
+};
+
+pub struct HttpResponse {
+    pub body: String,
+    pub modified: bool,
+}
+
+#[derive(Clone, Default)]
+struct CachedResponse {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    body: String,
+}
+
+/// Responses are cached per url and headers to send conditional requests,
+/// since other headers, like other credentials, can give other responses.
+#[derive(Default)]
+struct HttpCache(HashMap<(String, Vec<(String, String)>), CachedResponse>);
+
+enum Fetched {
+    Data(CachedResponse),
+    NotModified,
+}
+
+pub async fn fetch(url: &str, http: &HttpConfig) -> Result<HttpResponse, String> {
+    let mut headers = http
+        .headers
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), resolve_secrets(value)?)))
+        .collect::<Result<Vec<(String, String)>, String>>()?;
+    headers.sort();
+    let cache_key = (url.to_string(), headers.clone());
+    let cached = storage::try_get::<HttpCache>().and_then(|cache| cache.0.get(&cache_key).cloned());
+    let mut attempt = 0;
+    let fetched = loop {
+        match fetch_once(url, http, &headers, cached.as_ref()).await {
+            Ok(fetched) => break fetched,
+            Err(err) if attempt < http.retries => {
+                let delay = http.retry_delay(attempt);
+                error!("Error reading inputdata: {}, retrying in {}s", err, delay);
+                wait(delay).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+    match (fetched, cached) {
+        (Fetched::NotModified, Some(cached)) => Ok(HttpResponse {
+            body: cached.body,
+            modified: false,
+        }),
+        (Fetched::NotModified, None) => Err("Not modified, but nothing cached".to_string()),
+        (Fetched::Data(response), cached) => {
+            let modified = cached.is_none_or(|cached| cached.body != response.body);
+            let body = response.body.clone();
+            if storage::try_get::<HttpCache>().is_none() {
+                storage::store(HttpCache::default());
+            }
+            storage::get_mut::<HttpCache>()
+                .0
+                .insert(cache_key, response);
+            Ok(HttpResponse { body, modified })
+        }
+    }
+}
+
+async fn wait(seconds: f32) {
+    let until = get_time() + seconds as f64;
+    while get_time() < until {
+        next_frame().await;
+    }
+}
+
+/// Requests are blocking, so they are made in a thread while the coroutine waits.
+#[cfg(not(target_arch = "wasm32"))]
+async fn fetch_once(
+    url: &str,
+    http: &HttpConfig,
+    headers: &[(String, String)],
+    cached: Option<&CachedResponse>,
+) -> Result<Fetched, String> {
+    let (sender, receiver) = channel();
+    let url = url.to_string();
+    let timeout = Duration::from_secs_f32(http.timeout);
+    let headers = headers.to_vec();
+    let cached = cached.cloned().unwrap_or_default();
+    thread::spawn(move || {
+        let _ = sender.send(request(&url, timeout, &headers, &cached));
+    });
+    loop {
+        match receiver.try_recv() {
+            Ok(result) => return result,
+            Err(TryRecvError::Empty) => next_frame().await,
+            Err(TryRecvError::Disconnected) => return Err("Request was aborted".to_string()),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn request(
+    url: &str,
+    timeout: Duration,
+    headers: &[(String, String)],
+    cached: &CachedResponse,
+) -> Result<Fetched, String> {
+    let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+    let mut request = agent.get(url);
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    if let Some(etag) = &cached.etag {
+        request = request.set("If-None-Match", etag);
+    }
+    if let Some(last_modified) = &cached.last_modified {
+        request = request.set("If-Modified-Since", last_modified);
+    }
+    let response = request.call().map_err(|err| err.to_string())?;
+    if response.status() == 304 {
+        return Ok(Fetched::NotModified);
+    }
+    let etag = response.header("ETag").map(str::to_string);
+    let last_modified = response.header("Last-Modified").map(str::to_string);
+    let body = response.into_string().map_err(|err| err.to_string())?;
+    Ok(Fetched::Data(CachedResponse {
+        etag,
+        last_modified,
+        body,
+    }))
+}
+
+/// The browser handles conditional requests by itself.
+#[cfg(target_arch = "wasm32")]
+async fn fetch_once(
+    url: &str,
+    http: &HttpConfig,
+    headers: &[(String, String)],
+    _cached: Option<&CachedResponse>,
+) -> Result<Fetched, String> {
+    let timeout = get_time() + http.timeout as f64;
+    let mut request = headers
+        .iter()
+        .fold(
+            quad_net::http_request::RequestBuilder::new(url),
+            |request, (name, value)| request.header(name, value),
+        )
+        .send();
+    loop {
+        if let Some(result) = request.try_recv() {
+            return result
+                .map(|body| {
+                    Fetched::Data(CachedResponse {
+                        body,
+                        ..Default::default()
+                    })
+                })
+                .map_err(|err| err.to_string());
+        }
+        if get_time() > timeout {
+            return Err("Request timed out".to_string());
+        }
+        next_frame().await;
+    }
+}
@@ -0,0 +1,50 @@
+use nanoserde::DeJson;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, DeJson)]
+#[nserde(default)]
+pub struct HttpConfig {
+    pub timeout: f32,
+    pub retries: u32,
+    pub backoff: f32,
+    pub headers: HashMap<String, String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: 10.,
+            retries: 3,
+            backoff: 1.,
+            headers: HashMap::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    const MIN_TIMEOUT: f32 = 0.1;
+    const MAX_TIMEOUT: f32 = 300.;
+    const MAX_BACKOFF: f32 = 300.;
+
+    /// Keeps the timeout and backoff within limits that are safe to wait for.
+    pub fn clamped(self) -> Self {
+        let default = Self::default();
+        Self {
+            timeout: Some(self.timeout)
+                .filter(|timeout| timeout.is_finite())
+                .map_or(default.timeout, |timeout| {
+                    timeout.clamp(Self::MIN_TIMEOUT, Self::MAX_TIMEOUT)
+                }),
+            backoff: Some(self.backoff)
+                .filter(|backoff| backoff.is_finite())
+                .map_or(default.backoff, |backoff| {
+                    backoff.clamp(0., Self::MAX_BACKOFF)
+                }),
+            ..self
+        }
+    }
+
+    pub fn retry_delay(&self, attempt: u32) -> f32 {
+        (self.backoff * 2_f32.powi(attempt as i32)).min(Self::MAX_BACKOFF)
+    }
+}
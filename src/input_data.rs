@@ -1,43 +1,79 @@
 #![allow(clippy::question_mark)]
 
-use crate::{fish_data::FishData, legend::Legend};
-use macroquad::{file::load_string, prelude::error, window::next_frame};
+use crate::{
+    event_data::EventData, fish_config::FishConfig, fish_data::FishData, http_client,
+    http_config::HttpConfig, input_data_error::InputDataError, legend::Legend,
+    prop_value::PropValue,
+};
+use macroquad::{file::load_string, prelude::error};
 use nanoserde::{DeJson, SerJson};
-use quad_net::http_request::RequestBuilder;
+use std::collections::HashMap;
 
 #[derive(Clone, Default, DeJson, SerJson)]
 pub struct InputData {
     pub legend: Option<Legend>,
     pub school: Vec<FishData>,
+    #[nserde(default)]
+    pub props: HashMap<String, PropValue>,
+    #[nserde(default)]
+    pub events: Vec<EventData>,
 }
 
 impl InputData {
-    pub async fn load(path: String) -> Self {
-        let json = if Self::is_url(&path) {
-            Self::load_url(path).await
+    /// Returns nothing for streams, since their data arrives later.
+    pub async fn load(path: String, http: HttpConfig) -> Result<Option<Self>, InputDataError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if crate::input_stream::InputStream::is_stream(&path) {
+            return Ok(None);
+        }
+        Self::reload(path, http)
+            .await
+            .map(|(data, _)| Some(data))
+            .inspect_err(|err| error!("{}", err))
+    }
+
+    /// Also returns if the data has been modified since it was last loaded.
+    /// Streams are not reloaded, but read with `InputStream`.
+    pub async fn reload(path: String, http: HttpConfig) -> Result<(Self, bool), InputDataError> {
+        let (json, modified) = if Self::is_url(&path) {
+            let response = http_client::fetch(&path, &http)
+                .await
+                .map_err(|err| InputDataError::new(format!("Error reading inputdata: {}", err)))?;
+            (response.body, response.modified)
         } else {
-            load_string(path.as_str()).await.ok()
+            let json = load_string(path.as_str()).await.map_err(|err| {
+                InputDataError::new(format!("Error reading inputdata {}: {}", path, err))
+            })?;
+            (json, true)
         };
-        DeJson::deserialize_json(&json.unwrap_or_else(|| "{}".to_string())).unwrap_or_default()
+        Ok((Self::parse(&json)?, modified))
     }
 
-    fn is_url(path: &str) -> bool {
-        path.starts_with("http://") || path.starts_with("https://")
+    pub fn parse(json: &str) -> Result<Self, InputDataError> {
+        Ok(DeJson::deserialize_json(json)?)
     }
 
-    async fn load_url(path: String) -> Option<String> {
-        let mut request = RequestBuilder::new(path.as_str()).send();
-        loop {
-            if let Some(result) = request.try_recv() {
-                return match result {
-                    Ok(data) => Some(data),
-                    Err(error) => {
-                        error!("Error reading inputdata: {}", error);
-                        None
-                    }
-                };
-            }
-            next_frame().await;
-        }
+    /// Finds fish names that are missing in the fish configuration.
+    pub fn validate(&self, fish_configs: &HashMap<String, FishConfig>) -> Vec<String> {
+        let school = self
+            .school
+            .iter()
+            .enumerate()
+            .filter(|(_, fish_data)| !fish_configs.contains_key(&fish_data.fish))
+            .map(|(index, fish_data)| {
+                format!("school[{}]: Unknown fish {}", index, fish_data.fish)
+            });
+        let events = self.events.iter().enumerate().filter_map(|(index, event)| {
+            event
+                .fish
+                .as_ref()
+                .filter(|fish| !fish_configs.contains_key(*fish))
+                .map(|fish| format!("events[{}]: Unknown fish {}", index, fish))
+        });
+        school.chain(events).collect()
+    }
+
+    fn is_url(path: &str) -> bool {
+        path.starts_with("http://") || path.starts_with("https://")
     }
 }
@@ -0,0 +1,40 @@
+use nanoserde::DeJsonErr;
+use std::fmt;
+
+#[derive(Clone, Debug)]
+pub struct InputDataError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl InputDataError {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl From<DeJsonErr> for InputDataError {
+    fn from(err: DeJsonErr) -> Self {
+        Self {
+            message: format!("Error parsing inputdata: {}", err.msg),
+            line: Some(err.line + 1),
+            column: Some(err.col + 1),
+        }
+    }
+}
+
+impl fmt::Display for InputDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => {
+                write!(f, "{} at line {}, column {}", self.message, line, column)
+            }
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::{input_data::InputData, input_data_error::InputDataError};
+use macroquad::prelude::error;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    sync::mpsc::{Receiver, Sender, TryIter, channel},
+    thread,
+};
+#[cfg(unix)]
+use std::{
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    time::Duration,
+};
+
+type DataSender = Sender<Result<InputData, InputDataError>>;
+
+/// Newline-delimited `InputData` JSON documents read in the background.
+pub struct InputStream {
+    pub path: String,
+    data: Receiver<Result<InputData, InputDataError>>,
+    /// Socket file created when listening, which is removed again when dropped.
+    #[cfg(unix)]
+    socket: Option<String>,
+}
+
+impl InputStream {
+    const STDIN: &'static str = "-";
+    const PIPE: &'static str = "pipe:";
+    #[cfg(unix)]
+    const UNIX: &'static str = "unix:";
+    #[cfg(unix)]
+    const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+    pub fn is_stream(path: &str) -> bool {
+        #[cfg(unix)]
+        if path.starts_with(Self::UNIX) {
+            return true;
+        }
+        path == Self::STDIN || path.starts_with(Self::PIPE)
+    }
+
+    pub fn open(path: String) -> Self {
+        let (sender, data) = channel();
+        #[cfg(unix)]
+        if let Some(socket) = path.strip_prefix(Self::UNIX) {
+            let socket = Self::read_unix_socket(socket.to_string(), sender);
+            return Self { path, data, socket };
+        }
+        let source = path.clone();
+        thread::spawn(move || {
+            match source
+                .strip_prefix(Self::PIPE)
+                .filter(|pipe| !pipe.is_empty())
+            {
+                Some(pipe) => Self::read_pipe(pipe, &sender),
+                None => {
+                    Self::read_lines(std::io::stdin().lock(), &sender);
+                }
+            }
+        });
+        Self {
+            path,
+            data,
+            #[cfg(unix)]
+            socket: None,
+        }
+    }
+
+    pub fn data(&self) -> TryIter<'_, Result<InputData, InputDataError>> {
+        self.data.try_iter()
+    }
+
+    /// Reopens the named pipe every time a writer closes it. Other files are
+    /// only read once.
+    fn read_pipe(path: &str, sender: &DataSender) {
+        loop {
+            match File::open(path) {
+                Ok(file) => {
+                    if !Self::read_lines(BufReader::new(file), sender) || !Self::is_pipe(path) {
+                        return;
+                    }
+                }
+                Err(err) => {
+                    error!("Error opening inputdata pipe {}: {}", path, err);
+                    return;
+                }
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn is_pipe(path: &str) -> bool {
+        std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo())
+    }
+
+    #[cfg(not(unix))]
+    fn is_pipe(path: &str) -> bool {
+        path.starts_with(r"\\.\pipe\")
+    }
+
+    /// Connects to the socket if something is listening on it, otherwise
+    /// listens on it for connections. Returns the socket when listening.
+    #[cfg(unix)]
+    fn read_unix_socket(path: String, sender: DataSender) -> Option<String> {
+        match UnixStream::connect(&path) {
+            Ok(stream) => {
+                thread::spawn(move || Self::connect_unix_socket(&path, stream, &sender));
+                None
+            }
+            Err(_) => Self::listen_unix_socket(path, sender),
+        }
+    }
+
+    #[cfg(unix)]
+    fn connect_unix_socket(path: &str, stream: UnixStream, sender: &DataSender) {
+        let mut stream = stream;
+        while Self::read_lines(BufReader::new(stream), sender) {
+            stream = loop {
+                thread::sleep(Self::RECONNECT_DELAY);
+                if let Ok(stream) = UnixStream::connect(path) {
+                    break stream;
+                }
+            };
+        }
+    }
+
+    #[cfg(unix)]
+    fn listen_unix_socket(path: String, sender: DataSender) -> Option<String> {
+        // Remove a socket left behind by an earlier run, but never other files
+        if std::fs::metadata(&path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            let _ = std::fs::remove_file(&path);
+        }
+        match UnixListener::bind(&path) {
+            Ok(listener) => {
+                thread::spawn(move || {
+                    for stream in listener.incoming().flatten() {
+                        let sender = sender.clone();
+                        thread::spawn(move || Self::read_lines(BufReader::new(stream), &sender));
+                    }
+                });
+                Some(path)
+            }
+            Err(err) => {
+                error!("Error listening on inputdata socket {}: {}", path, err);
+                None
+            }
+        }
+    }
+
+    /// Returns false when nobody is receiving the data anymore.
+    fn read_lines(reader: impl BufRead, sender: &DataSender) -> bool {
+        for line in reader.lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    error!("Error reading inputdata: {}", err);
+                    break;
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            if sender.send(InputData::parse(&line)).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(unix)]
+impl Drop for InputStream {
+    fn drop(&mut self) {
+        if let Some(socket) = &self.socket {
+            let _ = std::fs::remove_file(socket);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use nanoserde::DeJson;
+
+#[derive(Debug, Default, Copy, Clone, DeJson)]
+pub enum LabelMode {
+    #[default]
+    Never,
+    Always,
+    Hover,
+    Highlighted,
+}
+
+impl LabelMode {
+    pub fn show_label(&self, highlighted: bool, hovered: bool) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Always => true,
+            Self::Hover => hovered,
+            Self::Highlighted => highlighted,
+        }
+    }
+}
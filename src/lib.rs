@@ -1,5 +1,17 @@
+pub mod bubble_emitter;
 pub mod collision;
 pub mod config;
+pub mod control_command;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod control_server;
+pub mod data_source;
+pub mod data_status;
+pub mod decoration;
+pub mod decoration_config;
+pub mod decoration_kind;
+pub mod effect;
+pub mod event_data;
+pub mod event_kind;
 pub mod fish;
 pub mod fish_area;
 pub mod fish_config;
@@ -7,14 +19,33 @@ pub mod fish_data;
 pub mod fish_legend;
 pub mod fish_speed;
 pub mod fish_tank;
+pub mod food;
+pub mod http_client;
+pub mod http_config;
 pub mod input_data;
+pub mod input_data_error;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod input_stream;
+pub mod label_mode;
 pub mod legend;
+pub mod life_stage;
 pub mod motion;
 pub mod movement;
+pub mod prop_value;
 pub mod resources;
 pub mod scene_config;
+pub mod scene_layer;
 pub mod scenes;
+pub mod secret;
 pub mod shaders;
+pub mod show_diagnostics;
 pub mod show_help;
+pub mod show_inspection;
 pub mod show_legend;
 pub mod show_text;
+pub mod sprite_animation;
+pub mod tank_state;
+pub mod tank_text;
+pub mod text_panel;
+pub mod tint;
+pub mod zone;
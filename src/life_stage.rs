@@ -0,0 +1,6 @@
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LifeStage {
+    Spawning,
+    Swimming,
+    Dying,
+}
@@ -4,7 +4,7 @@ mod shaders;
 use macroquad::{
     camera::{Camera2D, set_camera, set_default_camera},
     color::colors::{DARKBLUE, WHITE},
-    input::{KeyCode, MouseButton, is_key_pressed, is_mouse_button_pressed},
+    input::{KeyCode, MouseButton, is_key_down, is_key_pressed, is_mouse_button_pressed},
     material::{gl_use_default_material, gl_use_material},
     math::vec2,
     texture::{DrawTextureParams, FilterMode, draw_texture_ex, render_target},
@@ -16,6 +16,15 @@ use rusty_aquarium::{
     config::Config, fish_tank::FishTank, resources::Resources, show_help::ShowHelp,
     show_text::ShowText,
 };
+#[cfg(not(target_arch = "wasm32"))]
+use {
+    macroquad::{experimental::collections::storage, prelude::error},
+    nanoserde::{DeJson, SerJson},
+    rusty_aquarium::{
+        control_command::ControlCommand, control_server::ControlServer, input_data::InputData,
+    },
+    std::process::ExitCode,
+};
 
 fn window_conf() -> Conf {
     Conf {
@@ -26,10 +35,98 @@ fn window_conf() -> Conf {
     }
 }
 
-#[macroquad::main(window_conf())]
-async fn main() {
+fn shader_text(shader_activated: bool) -> ShowText {
+    if shader_activated {
+        ShowText::new("Activated shader")
+    } else {
+        ShowText::new("Disabled shader")
+    }
+}
+
+/// Checks an input data file against the fish configuration and prints any
+/// problems found. Returns the exit code for the program.
+#[cfg(not(target_arch = "wasm32"))]
+fn validate_input_data(path: &str) -> ExitCode {
+    let input_data = match std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|json| InputData::parse(&json).map_err(|err| err.to_string()))
+    {
+        Ok(input_data) => input_data,
+        Err(err) => {
+            println!("{}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config: Config = match std::fs::read_to_string("assets/config.json")
+        .map_err(|err| err.to_string())
+        .and_then(|json| DeJson::deserialize_json(&json).map_err(|err| err.to_string()))
+    {
+        Ok(config) => config,
+        Err(err) => {
+            println!("assets/config.json: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let problems = input_data.validate(&config.fishes);
+    if problems.is_empty() {
+        println!("{}: ok", path);
+        return ExitCode::SUCCESS;
+    }
+    for problem in problems {
+        println!("{}: {}", path, problem);
+    }
+    ExitCode::FAILURE
+}
+
+/// The program has no console of its own on Windows, so the validation output
+/// is written to the console of the command prompt that started it.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(index) = args.iter().position(|arg| arg == "--validate-inputdata") {
+        #[cfg(windows)]
+        attach_console();
+        return match args.get(index + 1) {
+            Some(path) => validate_input_data(path),
+            None => {
+                println!("Usage: rusty-aquarium --validate-inputdata FILE");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    macroquad::Window::from_config(window_conf(), run());
+    ExitCode::SUCCESS
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    macroquad::Window::from_config(window_conf(), run());
+}
+
+async fn run() {
     const SCR_W: f32 = 100.0;
     const SCR_H: f32 = 62.5;
+    const FISH_TYPE_KEYS: [KeyCode; 9] = [
+        KeyCode::Key1,
+        KeyCode::Key2,
+        KeyCode::Key3,
+        KeyCode::Key4,
+        KeyCode::Key5,
+        KeyCode::Key6,
+        KeyCode::Key7,
+        KeyCode::Key8,
+        KeyCode::Key9,
+    ];
 
     macroquad::file::set_pc_assets_folder("assets");
     let crt_render_target = render_target(screen_width() as u32, screen_height() as u32);
@@ -43,11 +140,67 @@ async fn main() {
     let mut fish_tank = FishTank::new();
     let mut show_text = ShowText::empty();
     let mut show_help = ShowHelp::new();
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut control_server: Option<ControlServer> = None;
 
     loop {
         if !fish_tank.loaded {
             Resources::load().await;
             fish_tank.add_resources();
+            #[cfg(not(target_arch = "wasm32"))]
+            {
+                control_server = storage::get::<Resources>()
+                    .config
+                    .control_port
+                    .and_then(|port| {
+                        ControlServer::start(port)
+                            .map_err(|err| error!("Failed starting control server: {}", err))
+                            .ok()
+                    });
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        for command in control_server.iter().flat_map(|server| server.commands()) {
+            match command {
+                ControlCommand::InputData(input_data) => {
+                    fish_tank.post_input_data(*input_data);
+                    show_text = ShowText::new("Received data");
+                }
+                ControlCommand::NextScene => {
+                    fish_tank.next_scene();
+                    show_text = ShowText::new("Next scene");
+                }
+                ControlCommand::Scene(index, reply) => {
+                    let shown = fish_tank.show_scene(index);
+                    if shown {
+                        show_text = ShowText::new(&format!("Scene {}", index));
+                    }
+                    let _ = reply.send(shown);
+                }
+                ControlCommand::Shader => {
+                    shader_activated = !shader_activated;
+                    show_text = shader_text(shader_activated);
+                }
+                ControlCommand::Legend => fish_tank.toggle_legend(),
+                ControlCommand::State(reply) => {
+                    let mut state = fish_tank.state();
+                    state.shader = shader_activated;
+                    let _ = reply.send(state.serialize_json());
+                }
+                ControlCommand::UpdateFish(fish_data, reply) => {
+                    let _ = reply.send(fish_tank.update_fish(*fish_data));
+                }
+                ControlCommand::RemoveFish(id, reply) => {
+                    let _ = reply.send(fish_tank.remove_fish_with_id(&id));
+                }
+                ControlCommand::AddFishes(fish_type, count, reply) => {
+                    let _ = reply.send(fish_tank.add_fishes(Some(&fish_type), count));
+                }
+                ControlCommand::RemoveFishes(fish_type, count, reply) => {
+                    let _ = reply.send(fish_tank.remove_fishes(Some(&fish_type), count));
+                }
+            }
         }
 
         #[cfg(not(target_arch = "wasm32"))]
@@ -56,13 +209,15 @@ async fn main() {
         }
         if is_key_pressed(KeyCode::Left) || is_mouse_button_pressed(MouseButton::Middle) {
             shader_activated = !shader_activated;
-            show_text = if shader_activated {
-                ShowText::new("Activated shader")
-            } else {
-                ShowText::new("Disabled shader")
-            };
+            show_text = shader_text(shader_activated);
         }
-        if is_key_pressed(KeyCode::Right) || is_mouse_button_pressed(MouseButton::Left) {
+        if is_mouse_button_pressed(MouseButton::Left) && fish_tank.is_inspecting() {
+            fish_tank.select_fish(vec2(SCR_W, SCR_H));
+        } else if is_mouse_button_pressed(MouseButton::Left)
+            && (is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift))
+        {
+            fish_tank.feed_at_mouse(vec2(SCR_W, SCR_H));
+        } else if is_key_pressed(KeyCode::Right) || is_mouse_button_pressed(MouseButton::Left) {
             fish_tank.next_scene();
             show_text = ShowText::new("Next scene");
         }
@@ -76,11 +231,29 @@ async fn main() {
         if is_key_pressed(KeyCode::Enter) {
             fish_tank.repopulate();
         }
+        for (index, key) in FISH_TYPE_KEYS.iter().enumerate() {
+            if is_key_pressed(*key) {
+                let fish_type = fish_tank
+                    .select_fish_type(Some(index))
+                    .unwrap_or("random fish");
+                show_text = ShowText::new(&format!("Selected {}", fish_type));
+            }
+        }
+        if is_key_pressed(KeyCode::Key0) {
+            fish_tank.select_fish_type(None);
+            show_text = ShowText::new("Selected random fish");
+        }
         if is_key_pressed(KeyCode::Up) {
-            fish_tank.add_fish();
+            show_text = match fish_tank.add_fish() {
+                Ok(()) => show_text,
+                Err(err) => ShowText::new(err),
+            };
         }
         if is_key_pressed(KeyCode::Down) {
-            fish_tank.remove_fish();
+            show_text = match fish_tank.remove_fish() {
+                Ok(()) => show_text,
+                Err(err) => ShowText::new(err),
+            };
         }
         if is_key_pressed(KeyCode::C) {
             show_text = ShowText::new("Updating config...");
@@ -94,14 +267,32 @@ async fn main() {
         if is_key_pressed(KeyCode::L) || is_key_pressed(KeyCode::I) {
             fish_tank.toggle_legend();
         }
+        if is_key_pressed(KeyCode::F) {
+            fish_tank.feed(None);
+            show_text = ShowText::new("Feeding fish");
+        }
+        if is_key_pressed(KeyCode::E) {
+            show_text = if fish_tank.toggle_diagnostics() {
+                ShowText::new("Showing diagnostics")
+            } else {
+                ShowText::new("Hiding diagnostics")
+            };
+        }
         if is_key_pressed(KeyCode::H) {
             show_help.toggle_show();
         }
+        if is_key_pressed(KeyCode::P) {
+            show_text = if fish_tank.toggle_inspection() {
+                ShowText::new("Inspecting fish")
+            } else {
+                ShowText::new("Stopped inspecting")
+            };
+        }
 
         // Update fish positions
         let delta = get_frame_time();
 
-        fish_tank.tick(delta);
+        fish_tank.tick(delta, vec2(SCR_W, SCR_H));
 
         // build camera with following coordinate system:
         // (0., 0)     .... (SCR_W, 0.)
@@ -177,6 +368,8 @@ async fn main() {
 
         show_text.draw(delta);
         fish_tank.draw_legend();
+        fish_tank.draw_inspection(vec2(SCR_W, SCR_H));
+        fish_tank.draw_diagnostics();
         show_help.draw();
 
         next_frame().await
@@ -12,6 +12,9 @@ pub struct Motion {
     pub acceleration: Vec2,
     pub rotation: f32,
     pub idle: bool,
+    pub facing_right: bool,
+    pub turn_time: f32,
+    pub turn_duration: f32,
 }
 
 impl Motion {
@@ -22,11 +25,13 @@ impl Motion {
     pub fn move_position(&mut self, delta: f32, motion: Motion, bounding_box: Rect) -> Motion {
         //debug!("x: {} y: {} d: {}", self.position.x, self.position.y, delta);
 
+        let mut motion = motion;
+        motion.turn(delta);
         let position = self.clamp(
             if motion.idle {
                 motion.position
             } else {
-                motion.position + motion.speed * delta
+                motion.position + motion.speed * motion.turn_scale() * delta
             },
             bounding_box,
         );
@@ -39,9 +44,46 @@ impl Motion {
             acceleration: motion.acceleration,
             rotation: motion.rotation,
             idle: motion.idle,
+            facing_right: motion.facing_right,
+            turn_time: motion.turn_time,
+            turn_duration: motion.turn_duration,
         }
     }
 
+    pub fn turn_scale(&self) -> f32 {
+        if self.turn_time > 0. && self.turn_duration > 0. {
+            (std::f32::consts::PI * self.turn_time / self.turn_duration)
+                .cos()
+                .abs()
+        } else {
+            1.
+        }
+    }
+
+    fn turn(&mut self, delta: f32) {
+        let swims_right = self.speed.x >= 0.;
+        if self.turn_time <= 0. && swims_right != self.facing_right {
+            if self.turn_duration > 0. {
+                self.turn_time = self.turn_duration;
+            } else {
+                self.facing_right = swims_right;
+            }
+        }
+        if self.turn_time > 0. {
+            self.turn_time = (self.turn_time - delta).max(0.);
+            if self.turn_time <= self.turn_duration / 2. {
+                self.facing_right = swims_right;
+            }
+        }
+    }
+
+    pub fn steer_towards(&mut self, target: Vec2) {
+        let direction = (target - self.position).signum();
+        self.speed = self.speed.abs() * direction;
+        self.acceleration = self.acceleration.abs() * direction;
+        self.idle = false;
+    }
+
     pub fn rotate(&mut self) {
         self.rotation = (self.speed.y / self.max_speed.y).abs() * Self::MAX_ROTATION;
         if self.speed.x * self.speed.y < 0. {
@@ -0,0 +1,48 @@
+use nanoserde::{DeJson, DeJsonErr, DeJsonState, DeJsonTok, SerJson, SerJsonState};
+use std::str::Chars;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PropValue {
+    Active(bool),
+    Level(f32),
+}
+
+impl PropValue {
+    pub fn is_active(&self) -> bool {
+        match self {
+            Self::Active(active) => *active,
+            Self::Level(level) => *level > 0.,
+        }
+    }
+
+    pub fn level(&self) -> f32 {
+        match self {
+            Self::Active(true) => 1.,
+            Self::Active(false) => 0.,
+            Self::Level(level) => level.clamp(0., 1.),
+        }
+    }
+}
+
+impl DeJson for PropValue {
+    fn de_json(state: &mut DeJsonState, input: &mut Chars) -> Result<Self, DeJsonErr> {
+        let value = match state.tok {
+            DeJsonTok::Bool(value) => Self::Active(value),
+            DeJsonTok::U64(value) => Self::Level(value as f32),
+            DeJsonTok::I64(value) => Self::Level(value as f32),
+            DeJsonTok::F64(value) => Self::Level(value as f32),
+            _ => return Err(state.err_token("bool or number")),
+        };
+        state.next_tok(input)?;
+        Ok(value)
+    }
+}
+
+impl SerJson for PropValue {
+    fn ser_json(&self, d: usize, state: &mut SerJsonState) {
+        match self {
+            Self::Active(value) => value.ser_json(d, state),
+            Self::Level(value) => value.ser_json(d, state),
+        }
+    }
+}
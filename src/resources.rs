@@ -1,4 +1,4 @@
-use crate::{config::Config, input_data::InputData};
+use crate::{config::Config, input_data::InputData, input_data_error::InputDataError};
 use macroquad::{
     color::colors::{BLACK, WHITE},
     experimental::{collections::storage, coroutines::start_coroutine},
@@ -11,10 +11,14 @@ use std::collections::HashMap;
 
 pub struct Resources {
     pub config: Config,
-    pub input_data: InputData,
+    /// Missing until a stream has sent its first data.
+    pub input_data: Option<InputData>,
+    pub input_data_error: Option<InputDataError>,
     pub backgrounds: Vec<Texture2D>,
     pub bubble_texture: Texture2D,
     pub fish_textures: HashMap<String, Texture2D>,
+    pub decoration_textures: HashMap<String, Texture2D>,
+    pub bubble_textures: HashMap<String, Texture2D>,
 }
 
 impl Resources {
@@ -24,21 +28,42 @@ impl Resources {
             .input_data_path
             .to_owned()
             .expect("input_data_path missing");
-        let input_data = InputData::load(input_data_path).await;
+        let (input_data, input_data_error) =
+            match InputData::load(input_data_path, config.http.clone()).await {
+                Ok(input_data) => (input_data, None),
+                Err(err) => (None, Some(err)),
+            };
         let bubble_texture: Texture2D =
             load_texture(&config.water_sprite.to_owned().unwrap_or_default()).await?;
         let backgrounds = config.background_textures().await;
         let mut fish_textures = HashMap::new();
+        let mut bubble_textures = HashMap::new();
         for (_key, fish) in config.fishes.iter() {
             fish_textures.insert(fish.texture.clone(), load_texture(&fish.texture).await?);
+            if let Some(texture) = &fish.bubble_emitter.texture {
+                bubble_textures.insert(texture.clone(), load_texture(texture).await?);
+            }
+        }
+        let mut decoration_textures = HashMap::new();
+        for scene in config.scenes.iter().flatten() {
+            for texture in scene
+                .decorations
+                .iter()
+                .filter_map(|decoration| decoration.texture.as_ref())
+            {
+                decoration_textures.insert(texture.clone(), load_texture(texture).await?);
+            }
         }
 
         Ok(Resources {
             config,
             input_data,
+            input_data_error,
             backgrounds,
             bubble_texture,
             fish_textures,
+            decoration_textures,
+            bubble_textures,
         })
     }
 
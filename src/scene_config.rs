@@ -1,7 +1,11 @@
 #![allow(clippy::question_mark)]
 
-use crate::legend::Legend;
+use crate::{
+    decoration_config::DecorationConfig, label_mode::LabelMode, legend::Legend,
+    scene_layer::SceneLayer, zone::Zone,
+};
 use nanoserde::DeJson;
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, DeJson)]
 #[nserde(default)]
@@ -10,6 +14,11 @@ pub struct SceneConfig {
     pub display_time: u32,
     pub background: Option<usize>,
     pub legend: Option<Legend>,
+    pub zones: HashMap<String, Zone>,
+    pub labels: LabelMode,
+    pub layers: Vec<SceneLayer>,
+    pub decorations: Vec<DecorationConfig>,
+    pub headers: HashMap<String, String>,
 }
 
 impl Default for SceneConfig {
@@ -19,6 +28,11 @@ impl Default for SceneConfig {
             display_time: 30,
             background: None,
             legend: None,
+            zones: HashMap::new(),
+            labels: LabelMode::Never,
+            layers: vec![],
+            decorations: vec![],
+            headers: HashMap::new(),
         }
     }
 }
@@ -30,6 +44,11 @@ impl SceneConfig {
             display_time,
             background: None,
             legend: None,
+            zones: HashMap::new(),
+            labels: LabelMode::Never,
+            layers: vec![],
+            decorations: vec![],
+            headers: HashMap::new(),
         }
     }
 }
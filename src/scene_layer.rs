@@ -0,0 +1,19 @@
+use nanoserde::DeJson;
+
+#[derive(Clone, Debug, DeJson)]
+#[nserde(default)]
+pub struct SceneLayer {
+    pub background: usize,
+    pub depth: f32,
+    pub parallax: f32,
+}
+
+impl Default for SceneLayer {
+    fn default() -> Self {
+        Self {
+            background: 0,
+            depth: 1.,
+            parallax: 0.,
+        }
+    }
+}
@@ -1,9 +1,14 @@
-use crate::{legend::Legend, scene_config::SceneConfig};
+use crate::{
+    decoration_config::DecorationConfig, label_mode::LabelMode, legend::Legend,
+    scene_config::SceneConfig, scene_layer::SceneLayer, zone::Zone,
+};
 use macroquad::{
     color::colors::WHITE,
-    math::Vec2,
+    math::{Vec2, vec2},
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
+    time::get_time,
 };
+use std::collections::HashMap;
 
 pub struct Scenes {
     current_scene: usize,
@@ -14,6 +19,8 @@ pub struct Scenes {
 }
 
 impl Scenes {
+    const PARALLAX_SPEED: f32 = 0.2;
+
     pub fn new(scenes: Vec<SceneConfig>, backgrounds: Vec<Texture2D>) -> Self {
         Self {
             current_scene: 0,
@@ -57,6 +64,34 @@ impl Scenes {
                 ..Default::default()
             },
         );
+        for zone in self.scenes[self.current_scene].zones.values() {
+            zone.draw();
+        }
+    }
+
+    pub fn draw_layer(&self, layer: &SceneLayer, rect: Vec2) {
+        if let Some(texture) = self.backgrounds.get(layer.background) {
+            let sway = layer.parallax * (get_time() as f32 * Self::PARALLAX_SPEED).sin();
+            draw_texture_ex(
+                texture,
+                sway - layer.parallax,
+                0.,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(rect.x + layer.parallax * 2., rect.y)),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    pub fn layers(&self) -> Vec<&SceneLayer> {
+        let mut layers = self.scenes[self.current_scene]
+            .layers
+            .iter()
+            .collect::<Vec<&SceneLayer>>();
+        layers.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+        layers
     }
 
     fn display_time(&self) -> u32 {
@@ -71,10 +106,26 @@ impl Scenes {
         self.scenes[self.current_scene].input_data_path.clone()
     }
 
+    pub fn headers(&self) -> &HashMap<String, String> {
+        &self.scenes[self.current_scene].headers
+    }
+
     pub fn legend(&self) -> Option<Legend> {
         self.scenes[self.current_scene].legend.clone()
     }
 
+    pub fn label_mode(&self) -> LabelMode {
+        self.scenes[self.current_scene].labels
+    }
+
+    pub fn decorations(&self) -> &[DecorationConfig] {
+        &self.scenes[self.current_scene].decorations
+    }
+
+    pub fn zone(&self, name: &str) -> Option<&Zone> {
+        self.scenes[self.current_scene].zones.get(name)
+    }
+
     pub fn next(&mut self) {
         self.time = 0.;
         self.current_scene += 1;
@@ -83,6 +134,23 @@ impl Scenes {
         }
     }
 
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.scenes.len() {
+            return false;
+        }
+        self.time = 0.;
+        self.current_scene = index;
+        true
+    }
+
+    pub fn current(&self) -> usize {
+        self.current_scene
+    }
+
+    pub fn count(&self) -> usize {
+        self.scenes.len()
+    }
+
     pub fn toggle_switching(&mut self) -> bool {
         self.time = 0.;
         self.switching = !self.switching;
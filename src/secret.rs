@@ -0,0 +1,145 @@
+/// Replaces `${env:NAME}`, `${file:PATH}` and `${basic:USER:PASSWORD}` in a
+/// value with the environment variable, the file contents, or the base64
+/// encoded credentials from the two environment variables. Errors never
+/// contain the secret itself.
+pub fn resolve_secrets(value: &str) -> Result<String, String> {
+    resolve_secrets_with(value, |name| std::env::var(name).ok())
+}
+
+/// Resolves the secrets with `env` looking up the environment variables.
+pub fn resolve_secrets_with(
+    value: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    let mut resolved = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or("Missing } in secret reference")?;
+        resolved.push_str(&resolve_secret(&rest[start + 2..end], &env)?);
+        rest = &rest[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn resolve_secret(
+    reference: &str,
+    env: &impl Fn(&str) -> Option<String>,
+) -> Result<String, String> {
+    match reference.split_once(':') {
+        Some(("env", name)) => env_secret(name, env),
+        Some(("file", path)) => std::fs::read_to_string(path)
+            .map(|secret| secret.trim_end().to_string())
+            .map_err(|err| format!("Error reading secret file {}: {}", path, err.kind())),
+        Some(("basic", names)) => {
+            let (user, password) = names
+                .split_once(':')
+                .ok_or("Basic auth secret needs a user and a password variable")?;
+            let credentials = format!("{}:{}", env_secret(user, env)?, env_secret(password, env)?);
+            Ok(base64(credentials.as_bytes()))
+        }
+        _ => Err(format!("Unknown secret reference {}", reference)),
+    }
+}
+
+fn env_secret(name: &str, env: &impl Fn(&str) -> Option<String>) -> Result<String, String> {
+    env(name).ok_or_else(|| format!("Environment variable {} is not set", name))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0_u32, |bits, (index, byte)| {
+            bits | ((*byte as u32) << (16 - 8 * index))
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(ALPHABET[((bits >> (18 - 6 * index)) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "hunter2";
+
+    fn resolve(value: &str) -> Result<String, String> {
+        resolve_secrets_with(value, |name| match name {
+            "TOKEN" => Some(SECRET.to_string()),
+            "USER" => Some("Aladdin".to_string()),
+            "PASSWORD" => Some("open sesame".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn env_reference_is_replaced() {
+        assert_eq!(resolve("Bearer ${env:TOKEN}").unwrap(), "Bearer hunter2");
+    }
+
+    #[test]
+    fn file_reference_is_replaced() {
+        let path =
+            std::env::temp_dir().join(format!("rusty-aquarium-test-secret-{}", std::process::id()));
+        std::fs::write(&path, format!("{}\n", SECRET)).unwrap();
+        let value = format!("token=${{file:{}}}; x", path.display());
+        let resolved = resolve(&value);
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(resolved.unwrap(), "token=hunter2; x");
+    }
+
+    #[test]
+    fn basic_reference_is_encoded() {
+        assert_eq!(
+            resolve("Basic ${basic:USER:PASSWORD}").unwrap(),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+    }
+
+    #[test]
+    fn base64_pads_output() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+    }
+
+    #[test]
+    fn value_without_references_is_unchanged() {
+        assert_eq!(resolve("text/plain").unwrap(), "text/plain");
+    }
+
+    #[test]
+    fn missing_brace_is_an_error() {
+        let err = resolve("Bearer ${env:TOKEN").unwrap_err();
+        assert_eq!(err, "Missing } in secret reference");
+    }
+
+    #[test]
+    fn unknown_scheme_is_an_error() {
+        let err = resolve("${vault:token}").unwrap_err();
+        assert_eq!(err, "Unknown secret reference vault:token");
+    }
+
+    #[test]
+    fn errors_never_contain_secrets() {
+        let err = resolve("${env:TOKEN} ${env:UNSET}").unwrap_err();
+        assert_eq!(err, "Environment variable UNSET is not set");
+        let err = resolve("${basic:TOKEN:UNSET}").unwrap_err();
+        assert!(!err.contains(SECRET));
+        let err = resolve("${file:/nonexistent/rusty-aquarium-secret}").unwrap_err();
+        assert!(!err.contains(SECRET));
+    }
+}
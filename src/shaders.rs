@@ -1,4 +1,7 @@
-use macroquad::prelude::{Material, ShaderSource, load_material};
+use macroquad::{
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation, PipelineParams},
+    prelude::{Material, MaterialParams, ShaderSource, load_material},
+};
 use macroquad_particles::ParticleMaterial;
 
 pub mod crt {
@@ -177,3 +180,70 @@ pub mod water_particle {
         }
     "#;
 }
+
+pub mod fish_wiggle {
+    use super::*;
+
+    #[allow(dead_code)]
+    pub fn material() -> Result<Material, macroquad::Error> {
+        load_material(
+            SHADER_SOURCE,
+            MaterialParams {
+                pipeline_params: PipelineParams {
+                    color_blend: Some(BlendState::new(
+                        Equation::Add,
+                        BlendFactor::Value(BlendValue::SourceAlpha),
+                        BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                    )),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+    }
+
+    #[allow(dead_code)]
+    const SHADER_SOURCE: ShaderSource = ShaderSource::Glsl {
+        vertex: fish_wiggle::VERTEX,
+        fragment: fish_wiggle::FRAGMENT,
+    };
+
+    #[allow(dead_code)]
+    const FRAGMENT: &str = r#"#version 100
+        precision lowp float;
+
+        varying vec4 color;
+        varying vec2 uv;
+
+        uniform sampler2D Texture;
+
+        void main() {
+            gl_FragColor = texture2D(Texture, uv) * color;
+        }
+    "#;
+
+    // The normal attribute carries per vertex wiggle data:
+    // x = amplitude, y = phase, z = distance from head (0) to tail (1), w = frequency
+    #[allow(dead_code)]
+    const VERTEX: &str = r#"#version 100
+        attribute vec3 position;
+        attribute vec2 texcoord;
+        attribute vec4 color0;
+        attribute vec4 normal;
+
+        varying lowp vec2 uv;
+        varying lowp vec4 color;
+
+        uniform mat4 Model;
+        uniform mat4 Projection;
+        uniform vec4 _Time;
+
+        void main() {
+            float tail = normal.z * normal.z;
+            float sway = sin(_Time.x * normal.w + normal.y - normal.z * 3.0) * normal.x * tail;
+            gl_Position = Projection * Model * vec4(position + vec3(0.0, sway, 0.0), 1);
+            color = color0 / 255.0;
+            uv = texcoord;
+        }
+    "#;
+}
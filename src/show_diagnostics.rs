@@ -0,0 +1,36 @@
+use crate::text_panel::{draw_text_panel, measure_text_panel};
+use macroquad::{math::vec2, window::screen_height};
+
+pub struct ShowDiagnostics {
+    pub showing: bool,
+}
+
+impl Default for ShowDiagnostics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShowDiagnostics {
+    const MARGIN: f32 = 50.;
+
+    pub fn new() -> Self {
+        Self { showing: false }
+    }
+
+    pub fn toggle_show(&mut self) -> bool {
+        self.showing = !self.showing;
+        self.showing
+    }
+
+    pub fn draw(&self, lines: &[String]) {
+        if !self.showing {
+            return;
+        }
+        let size = measure_text_panel(lines);
+        draw_text_panel(
+            lines,
+            vec2(Self::MARGIN, screen_height() - Self::MARGIN - size.y),
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use crate::text_panel::draw_text_panel;
+use macroquad::math::vec2;
+
+pub struct ShowInspection {
+    pub showing: bool,
+}
+
+impl Default for ShowInspection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShowInspection {
+    const MARGIN: f32 = 50.;
+
+    pub fn new() -> Self {
+        Self { showing: false }
+    }
+
+    pub fn toggle_show(&mut self) -> bool {
+        self.showing = !self.showing;
+        self.showing
+    }
+
+    pub fn draw(&self, fields: &[(String, String)]) {
+        if !self.showing || fields.is_empty() {
+            return;
+        }
+        let lines = fields
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<String>>();
+        draw_text_panel(&lines, vec2(Self::MARGIN, Self::MARGIN));
+    }
+}
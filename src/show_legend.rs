@@ -1,4 +1,6 @@
-use crate::{fish_legend::FishLegend, legend::Legend, resources::Resources};
+use crate::{
+    fish_config::FishConfig, fish_legend::FishLegend, legend::Legend, resources::Resources,
+};
 use macroquad::{
     color::{Color, colors::WHITE},
     experimental::collections::storage,
@@ -83,13 +85,18 @@ impl ShowLegend {
         let resources = storage::get::<Resources>();
         let fish_textures = &resources.fish_textures;
         let fish_configs = &resources.config.fishes;
-        let max_fish_height = self.find_max_height(fish_textures);
+        let max_fish_height = self.find_max_height(fish_configs, fish_textures);
         let mut offset_y = start_y;
         for fish_legend in fish_legends.iter() {
             if let Some(fish_config) = fish_configs.get(&fish_legend.fish) {
                 if let Some(texture) = fish_textures.get(&fish_config.texture) {
-                    offset_y =
-                        self.draw_fish_legend(offset_y, max_fish_height, fish_legend, texture);
+                    offset_y = self.draw_fish_legend(
+                        offset_y,
+                        max_fish_height,
+                        fish_legend,
+                        fish_config,
+                        texture,
+                    );
                 }
             }
         }
@@ -100,9 +107,11 @@ impl ShowLegend {
         offset_y: f32,
         max_fish_height: f32,
         fish_legend: &FishLegend,
+        fish_config: &FishConfig,
         texture: &Texture2D,
     ) -> f32 {
-        let fish_height = Self::FISH_SIZE / (texture.width() / texture.height());
+        let frame_size = fish_config.frame_size(texture);
+        let fish_height = Self::FISH_SIZE / (frame_size.x / frame_size.y);
         draw_texture_ex(
             texture,
             Self::MARGIN * 2.,
@@ -110,6 +119,9 @@ impl ShowLegend {
             WHITE,
             DrawTextureParams {
                 dest_size: Some(vec2(Self::FISH_SIZE, fish_height)),
+                source: fish_config
+                    .animation
+                    .map(|animation| animation.source(texture, 0)),
                 ..Default::default()
             },
         );
@@ -126,10 +138,19 @@ impl ShowLegend {
         offset_y + max_fish_height + Self::LINE_OFFSET
     }
 
-    fn find_max_height(&self, fish_textures: &HashMap<String, Texture2D>) -> f32 {
-        fish_textures
+    fn find_max_height(
+        &self,
+        fish_configs: &HashMap<String, FishConfig>,
+        fish_textures: &HashMap<String, Texture2D>,
+    ) -> f32 {
+        fish_configs
             .values()
-            .map(|texture| Self::FISH_SIZE / (texture.width() / texture.height()))
+            .filter_map(|fish_config| {
+                fish_textures
+                    .get(&fish_config.texture)
+                    .map(|texture| fish_config.frame_size(texture))
+            })
+            .map(|frame_size| Self::FISH_SIZE / (frame_size.x / frame_size.y))
             .max_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap()
     }
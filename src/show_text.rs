@@ -1,16 +1,16 @@
 use macroquad::{color::colors::WHITE, text::draw_text};
 
 pub struct ShowText {
-    text: &'static str,
+    text: String,
     time: f32,
     x: f32,
     y: f32,
 }
 
 impl ShowText {
-    pub fn new(text: &'static str) -> Self {
+    pub fn new(text: &str) -> Self {
         Self {
-            text,
+            text: text.to_string(),
             time: 2.,
             x: 20.,
             y: 40.,
@@ -19,7 +19,7 @@ impl ShowText {
 
     pub fn empty() -> Self {
         Self {
-            text: "",
+            text: String::new(),
             time: 0.,
             x: 0.,
             y: 0.,
@@ -29,7 +29,7 @@ impl ShowText {
     pub fn draw(&mut self, delta: f32) {
         if self.time > 0. {
             self.time -= delta;
-            draw_text(self.text, self.x, self.y, 40., WHITE);
+            draw_text(&self.text, self.x, self.y, 40., WHITE);
         }
     }
 }
@@ -0,0 +1,46 @@
+use macroquad::{
+    math::{Rect, Vec2, vec2},
+    texture::Texture2D,
+};
+use nanoserde::DeJson;
+
+#[derive(Clone, Copy, Debug, DeJson)]
+#[nserde(default)]
+pub struct SpriteAnimation {
+    pub columns: u32,
+    pub rows: u32,
+    pub frames: u32,
+    pub fps: f32,
+}
+
+impl Default for SpriteAnimation {
+    fn default() -> Self {
+        Self {
+            columns: 1,
+            rows: 1,
+            frames: 1,
+            fps: 10.,
+        }
+    }
+}
+
+impl SpriteAnimation {
+    pub fn frame_size(&self, texture: &Texture2D) -> Vec2 {
+        vec2(
+            texture.width() / self.columns.max(1) as f32,
+            texture.height() / self.rows.max(1) as f32,
+        )
+    }
+
+    pub fn source(&self, texture: &Texture2D, frame: u32) -> Rect {
+        let size = self.frame_size(texture);
+        let frame = frame % self.frames.max(1);
+        let columns = self.columns.max(1);
+        Rect {
+            x: (frame % columns) as f32 * size.x,
+            y: (frame / columns) as f32 * size.y,
+            w: size.x,
+            h: size.y,
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use nanoserde::SerJson;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, Default, SerJson)]
+pub struct TankState {
+    pub scene: usize,
+    pub scenes: usize,
+    pub switching_scenes: bool,
+    pub legend: bool,
+    pub shader: bool,
+    pub fishes: HashMap<String, usize>,
+    pub stale: bool,
+    pub error: Option<String>,
+}
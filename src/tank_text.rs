@@ -0,0 +1,29 @@
+use macroquad::{
+    color::Color,
+    text::{TextDimensions, TextParams, camera_font_scale, draw_text_ex, measure_text},
+};
+
+pub fn draw_tank_text(text: &str, x: f32, y: f32, font_size: f32, color: Color) {
+    let (font_size, font_scale, font_scale_aspect) = camera_font_scale(font_size);
+    draw_text_ex(
+        text,
+        x,
+        y,
+        TextParams {
+            font_size,
+            font_scale,
+            font_scale_aspect,
+            color,
+            ..Default::default()
+        },
+    );
+}
+
+pub fn measure_tank_text(text: &str, font_size: f32) -> TextDimensions {
+    let (font_size, font_scale, font_scale_aspect) = camera_font_scale(font_size);
+    let dimensions = measure_text(text, None, font_size, font_scale);
+    TextDimensions {
+        width: dimensions.width * font_scale_aspect,
+        ..dimensions
+    }
+}
@@ -0,0 +1,32 @@
+use macroquad::{
+    color::{Color, colors::WHITE},
+    math::{Vec2, vec2},
+    shapes::draw_rectangle,
+    text::{draw_text, measure_text},
+};
+
+const BACKGROUND_COLOR: Color = Color::new(0.1, 0.1, 0.1, 0.7);
+const FONT_COLOR: Color = WHITE;
+const PADDING: f32 = 20.;
+const FONT_SIZE: f32 = 30.;
+const LINE_OFFSET: f32 = 8.;
+
+pub fn measure_text_panel(lines: &[String]) -> Vec2 {
+    let width = lines
+        .iter()
+        .map(|line| measure_text(line, None, FONT_SIZE as u16, 1.0).width)
+        .fold(0., f32::max);
+    let height = lines.len() as f32 * (FONT_SIZE + LINE_OFFSET);
+    vec2(width + PADDING * 2., height + PADDING * 2.)
+}
+
+/// Draws the lines on a dark panel with its top left corner at `position`.
+pub fn draw_text_panel(lines: &[String], position: Vec2) {
+    let size = measure_text_panel(lines);
+    draw_rectangle(position.x, position.y, size.x, size.y, BACKGROUND_COLOR);
+    let mut offset_y = position.y + PADDING + FONT_SIZE;
+    for line in lines.iter() {
+        draw_text(line, position.x + PADDING, offset_y, FONT_SIZE, FONT_COLOR);
+        offset_y += FONT_SIZE + LINE_OFFSET;
+    }
+}
@@ -0,0 +1,51 @@
+use macroquad::color::{Color, colors::*};
+
+pub fn parse_color(value: &str) -> Option<Color> {
+    match value.strip_prefix('#') {
+        Some(hex) => parse_hex(hex),
+        None => named_color(value),
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    match hex.len() {
+        6 => Some(Color::from_hex(value)),
+        8 => {
+            let [r, g, b, a] = value.to_be_bytes();
+            Some(Color::from_rgba(r, g, b, a))
+        }
+        _ => None,
+    }
+}
+
+fn named_color(name: &str) -> Option<Color> {
+    let color = match name.to_lowercase().as_str() {
+        "white" => WHITE,
+        "black" => BLACK,
+        "gray" | "grey" => GRAY,
+        "lightgray" | "lightgrey" => LIGHTGRAY,
+        "darkgray" | "darkgrey" => DARKGRAY,
+        "yellow" => YELLOW,
+        "gold" => GOLD,
+        "orange" => ORANGE,
+        "pink" => PINK,
+        "red" => RED,
+        "maroon" => MAROON,
+        "green" => GREEN,
+        "lime" => LIME,
+        "darkgreen" => DARKGREEN,
+        "skyblue" => SKYBLUE,
+        "blue" => BLUE,
+        "darkblue" => DARKBLUE,
+        "purple" => PURPLE,
+        "violet" => VIOLET,
+        "darkpurple" => DARKPURPLE,
+        "beige" => BEIGE,
+        "brown" => BROWN,
+        "darkbrown" => DARKBROWN,
+        "magenta" => MAGENTA,
+        _ => return None,
+    };
+    Some(color)
+}
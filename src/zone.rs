@@ -0,0 +1,34 @@
+#![allow(clippy::question_mark)]
+
+use crate::{fish_area::FishArea, tank_text::draw_tank_text};
+use macroquad::{color::Color, math::Rect};
+use nanoserde::DeJson;
+
+#[derive(Clone, Debug, DeJson)]
+pub struct Zone {
+    #[nserde(proxy = "FishArea")]
+    pub area: Rect,
+    pub label: Option<String>,
+}
+
+impl Zone {
+    const FONT_COLOR: Color = Color::new(1., 1., 1., 0.6);
+    const FONT_SIZE: f32 = 2.5;
+    const MARGIN: f32 = 1.;
+
+    pub fn draw(&self) {
+        if let Some(label) = &self.label {
+            draw_tank_text(
+                label,
+                self.area.x + Self::MARGIN,
+                self.area.y + Self::MARGIN + Self::FONT_SIZE,
+                Self::FONT_SIZE,
+                Self::FONT_COLOR,
+            );
+        }
+    }
+
+    pub fn constrain(&self, area: Rect) -> Rect {
+        area.intersect(self.area).unwrap_or(self.area)
+    }
+}